@@ -0,0 +1,565 @@
+//! The system call interface shared by the kernel and userland.
+//!
+//! A system call is made with `svc #n`, where `n` is one of the numbers in
+//! `nr`. Arguments are passed in `x0` to `x5`; a call taking fewer arguments
+//! ignores the remaining registers. On return, `x0` holds the result and `x7`
+//! the status: `0` on success, otherwise the code of an `OsError`. The few
+//! calls with a second result return it in `x1`. Every other register is
+//! preserved.
+//!
+//! A call made with a number that is not in `nr` fails with
+//! `OsError::NoSuchSyscall`.
+//!
+//! `svc!` and `svc_raw!` make a call from the kernel, `std` or a user program.
+//! The crate expanding them must enable `#![feature(asm)]`.
+
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(test)]
+extern crate core;
+
+use core::fmt;
+
+/// System call numbers.
+pub mod nr {
+    pub const SLEEP: u16 = 1;
+    pub const EXIT: u16 = 2;
+    pub const FUTEX_WAIT: u16 = 3;
+    pub const FUTEX_WAKE: u16 = 4;
+    pub const PIPE: u16 = 5;
+    pub const READ: u16 = 6;
+    pub const WRITE: u16 = 7;
+    pub const CLOSE: u16 = 8;
+    pub const SEND: u16 = 9;
+    pub const RECV: u16 = 10;
+    pub const SHM_CREATE: u16 = 11;
+    pub const SHM_MAP: u16 = 12;
+    pub const SHM_DROP: u16 = 13;
+    pub const KILL: u16 = 14;
+    pub const SIGACTION: u16 = 15;
+    pub const SIGPROCMASK: u16 = 16;
+    pub const SIGRETURN: u16 = 17;
+    pub const GETPID: u16 = 18;
+    pub const GETPPID: u16 = 19;
+    pub const GETRLIMIT: u16 = 20;
+    pub const SETRLIMIT: u16 = 21;
+    pub const SCHED_SETAFFINITY: u16 = 22;
+    pub const SCHED_GETAFFINITY: u16 = 23;
+    pub const MMAP: u16 = 24;
+    pub const MUNMAP: u16 = 25;
+    pub const CLOCK_GETTIME: u16 = 26;
+    pub const NANOSLEEP: u16 = 27;
+    pub const SETTIME: u16 = 28;
+    pub const YIELD: u16 = 29;
+    pub const SCHED_GETPARAM: u16 = 30;
+    pub const OPEN: u16 = 31;
+    pub const SEEK: u16 = 32;
+    pub const ARGS: u16 = 33;
+
+    /// One more than the highest system call number.
+    pub const COUNT: usize = 34;
+}
+
+/// Signal numbers and the arguments of `sigaction` and `sigprocmask`.
+pub mod signal {
+    /// Type alias for the type of a signal number.
+    pub type Signal = u32;
+
+    /// Number of signals. Valid signal numbers are `1` to `NSIG - 1`.
+    pub const NSIG: Signal = 32;
+
+    /// Interrupt from the console (Ctrl-C).
+    pub const SIGINT: Signal = 2;
+    /// Kill. Cannot be handled, ignored or blocked.
+    pub const SIGKILL: Signal = 9;
+    /// User-defined signal 1.
+    pub const SIGUSR1: Signal = 10;
+    /// User-defined signal 2.
+    pub const SIGUSR2: Signal = 12;
+    /// Termination request.
+    pub const SIGTERM: Signal = 15;
+    /// A child process exited. Ignored by default.
+    pub const SIGCHLD: Signal = 17;
+
+    /// Value of a signal handler that selects the default action.
+    pub const SIG_DFL: u64 = 0;
+    /// Value of a signal handler that ignores the signal.
+    pub const SIG_IGN: u64 = 1;
+
+    /// `how` of `sigprocmask` adding the signals of the set to the blocked
+    /// mask.
+    pub const SIG_BLOCK: u64 = 0;
+    /// `how` of `sigprocmask` removing the signals of the set from the blocked
+    /// mask.
+    pub const SIG_UNBLOCK: u64 = 1;
+    /// `how` of `sigprocmask` replacing the blocked mask with the set.
+    pub const SIG_SETMASK: u64 = 2;
+}
+
+/// Resources limited by `getrlimit` and `setrlimit`.
+pub mod resource {
+    /// Bytes of memory: the stack, shared memory and `mmap` memory.
+    pub const MEMORY: u64 = 0;
+    /// Number of open files.
+    pub const FILES: u64 = 1;
+    /// Number of children that exist at once.
+    pub const CHILDREN: u64 = 2;
+    /// Milliseconds of CPU time.
+    pub const CPU_TIME: u64 = 3;
+
+    /// Value of a limit that is never reached.
+    pub const UNLIMITED: u64 = !0;
+}
+
+/// Clocks read by `clock_gettime`.
+pub mod clock {
+    use OsError;
+
+    /// Time since boot. Never set, never goes backwards.
+    pub const CLOCK_MONOTONIC: u64 = 0;
+    /// Time since the Unix epoch, as set with `settime`.
+    pub const CLOCK_REALTIME: u64 = 1;
+
+    /// Nanoseconds in a second; the nanoseconds of a time are below it.
+    pub const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+    /// Longest sleep requested from the kernel at once, which counts in 64-bit
+    /// microseconds.
+    pub const MAX_SLEEP_SECS: u64 = 1 << 32;
+
+    /// A time or a duration in seconds and nanoseconds below `NANOS_PER_SEC`.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Timespec {
+        pub secs: u64,
+        pub nanos: u32,
+    }
+
+    impl Timespec {
+        /// Returns `self + other`, or `None` if the seconds overflow.
+        pub fn checked_add(&self, other: &Timespec) -> Option<Timespec> {
+            let mut secs = self.secs.checked_add(other.secs)?;
+            let mut nanos = self.nanos + other.nanos;
+            if nanos as u64 >= NANOS_PER_SEC {
+                nanos -= NANOS_PER_SEC as u32;
+                secs = secs.checked_add(1)?;
+            }
+            Some(Timespec { secs, nanos })
+        }
+
+        /// Returns `self - other`, or `None` if `other` is later than `self`.
+        pub fn checked_sub(&self, other: &Timespec) -> Option<Timespec> {
+            let mut secs = self.secs.checked_sub(other.secs)?;
+            let nanos = if self.nanos >= other.nanos {
+                self.nanos - other.nanos
+            } else {
+                secs = secs.checked_sub(1)?;
+                self.nanos + (NANOS_PER_SEC as u32 - other.nanos)
+            };
+            Some(Timespec { secs, nanos })
+        }
+    }
+
+    /// Sleeps for `t` with `nanosleep`, which makes the system call: asks for
+    /// at most `MAX_SLEEP_SECS` at once and sleeps again for the remaining
+    /// time when a signal interrupts the sleep. Gives up on any other error.
+    pub fn sleep<F>(t: Timespec, mut nanosleep: F)
+        where F: FnMut(Timespec) -> Result<(), (OsError, Timespec)>
+    {
+        let mut left = t;
+        while left.secs > 0 || left.nanos > 0 {
+            let chunk = Timespec { secs: left.secs.min(MAX_SLEEP_SECS), nanos: left.nanos };
+            let later = left.secs - chunk.secs;
+            left = match nanosleep(chunk) {
+                Ok(()) => Timespec { secs: later, nanos: 0 },
+                Err((OsError::Interrupted, rem)) => Timespec { secs: later + rem.secs, nanos: rem.nanos },
+                Err(_) => return,
+            };
+        }
+    }
+}
+
+/// The arguments of a process as copied by the `args` system call: the name
+/// of the process followed by its arguments, each terminated by a NUL byte.
+pub mod args {
+    /// Returns the number of bytes `args` take packed.
+    pub fn packed_len<'a, I: IntoIterator<Item = &'a [u8]>>(args: I) -> usize {
+        args.into_iter().map(|arg| arg.len() + 1).sum()
+    }
+
+    /// Packs `args` into the start of `buf` and returns the number of bytes
+    /// written. Panics if `buf` is shorter than `packed_len(args)`.
+    pub fn pack<'a, I: IntoIterator<Item = &'a [u8]>>(args: I, buf: &mut [u8]) -> usize {
+        let mut n = 0;
+        for arg in args {
+            buf[n..n + arg.len()].copy_from_slice(arg);
+            buf[n + arg.len()] = 0;
+            n += arg.len() + 1;
+        }
+        n
+    }
+
+    /// Returns an iterator over the arguments packed in `buf`. Bytes after
+    /// the last NUL byte are not an argument.
+    pub fn split<'a>(buf: &'a [u8]) -> Split<'a> {
+        Split { rest: buf }
+    }
+
+    /// An iterator over packed arguments, returned by `split`.
+    #[derive(Debug, Clone)]
+    pub struct Split<'a> {
+        rest: &'a [u8],
+    }
+
+    impl<'a> Iterator for Split<'a> {
+        type Item = &'a [u8];
+
+        fn next(&mut self) -> Option<&'a [u8]> {
+            let end = self.rest.iter().position(|b| *b == 0)?;
+            let arg = &self.rest[..end];
+            self.rest = &self.rest[end + 1..];
+            Some(arg)
+        }
+    }
+}
+
+/// Origins of the offset passed to `seek`.
+pub mod seek {
+    /// The offset is from the start of the file.
+    pub const SEEK_SET: u64 = 0;
+    /// The offset is from the current position.
+    pub const SEEK_CUR: u64 = 1;
+    /// The offset is from the end of the file.
+    pub const SEEK_END: u64 = 2;
+}
+
+/// The header of a user program binary, loaded by the kernel's `run` command.
+///
+/// A program is a flat, position-independent binary starting with a
+/// `HEADER_SIZE` byte header: a branch over the header to the entry point,
+/// `MAGIC`, and the little-endian size of the image in memory, `.bss`
+/// included. The program is entered at its first byte.
+pub mod image {
+    /// Bytes 4 to 7 of a program binary.
+    pub const MAGIC: [u8; 4] = *b"ROSB";
+
+    /// Size in bytes of the header.
+    pub const HEADER_SIZE: usize = 16;
+
+    /// Returns the size in memory of the program binary `image`, or `None` if
+    /// it does not start with a valid header. The size is never smaller than
+    /// the binary.
+    pub fn mem_size(image: &[u8]) -> Option<usize> {
+        if image.len() < HEADER_SIZE || image[4..8] != MAGIC {
+            return None;
+        }
+
+        let size = image[8..16].iter().rev().fold(0u64, |n, b| n << 8 | *b as u64);
+        if size < image.len() as u64 {
+            return None;
+        }
+        Some(size as usize)
+    }
+}
+
+/// Size in bytes of the payload of a message passed to `send` and `recv`.
+pub const MSG_SIZE: usize = 32;
+
+/// Timeout of `recv` that blocks until a message arrives.
+pub const NO_TIMEOUT: u64 = !0;
+
+/// Alignment of the memory returned by `shm_create`, `shm_map` and `mmap`,
+/// whose sizes are rounded up to a multiple of it.
+pub const PAGE_SIZE: usize = 1 << 12;
+
+/// Number of argument registers, `x0` to `x5`.
+pub const NARGS: usize = 6;
+
+/// Status in `x7` of a system call that succeeded.
+pub const OK: u64 = 0;
+
+/// Why a system call failed. The discriminant is the status returned in
+/// `x7`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u64)]
+pub enum OsError {
+    /// An argument is invalid.
+    InvalidArgument = 1,
+    /// The call would block but its condition no longer holds, such as a
+    /// futex word that changed before `futex_wait`.
+    WouldBlock = 2,
+    /// A file descriptor is not open or does not support the operation.
+    BadFileDescriptor = 3,
+    /// Writing to a pipe whose read ends are closed.
+    BrokenPipe = 4,
+    /// No process has the given ID.
+    NoSuchProcess = 5,
+    /// A blocking call timed out.
+    TimedOut = 6,
+    /// Creating something that already exists.
+    AlreadyExists = 7,
+    /// A named object does not exist.
+    NotFound = 8,
+    /// The kernel is out of memory, or the process is at its memory limit.
+    NoMemory = 9,
+    /// A signal interrupted a waiting call.
+    Interrupted = 10,
+    /// The process has as many files open as its limit allows.
+    TooManyFiles = 11,
+    /// The process is not allowed to do what it asked.
+    PermissionDenied = 12,
+    /// There is no system call with the given number.
+    NoSuchSyscall = 13,
+    /// A pointer argument is outside of the memory of the process.
+    BadAddress = 14,
+    /// The device or the file system failed.
+    Io = 15,
+    /// A status this version of the interface does not know.
+    Unknown = 0xffff,
+}
+
+impl OsError {
+    /// Returns the error with status `code`, `Unknown` if there is none.
+    pub fn from_code(code: u64) -> OsError {
+        use OsError::*;
+
+        match code {
+            1 => InvalidArgument,
+            2 => WouldBlock,
+            3 => BadFileDescriptor,
+            4 => BrokenPipe,
+            5 => NoSuchProcess,
+            6 => TimedOut,
+            7 => AlreadyExists,
+            8 => NotFound,
+            9 => NoMemory,
+            10 => Interrupted,
+            11 => TooManyFiles,
+            12 => PermissionDenied,
+            13 => NoSuchSyscall,
+            14 => BadAddress,
+            15 => Io,
+            _ => Unknown,
+        }
+    }
+
+    /// Returns the status in `x7` for this error.
+    pub fn code(self) -> u64 {
+        self as u64
+    }
+
+    /// Returns a short description of the error.
+    pub fn description(&self) -> &'static str {
+        use OsError::*;
+
+        match *self {
+            InvalidArgument => "invalid argument",
+            WouldBlock => "operation would block",
+            BadFileDescriptor => "bad file descriptor",
+            BrokenPipe => "broken pipe",
+            NoSuchProcess => "no such process",
+            TimedOut => "timed out",
+            AlreadyExists => "already exists",
+            NotFound => "not found",
+            NoMemory => "out of memory",
+            Interrupted => "interrupted",
+            TooManyFiles => "too many open files",
+            PermissionDenied => "permission denied",
+            NoSuchSyscall => "no such system call",
+            BadAddress => "bad address",
+            Io => "input/output error",
+            Unknown => "unknown error",
+        }
+    }
+}
+
+impl fmt::Display for OsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Returns the status in `x7` for the outcome `r`.
+pub fn status(r: Result<(), OsError>) -> u64 {
+    match r {
+        Ok(()) => OK,
+        Err(e) => e.code(),
+    }
+}
+
+/// Returns the outcome for the status `status` read from `x7`.
+pub fn result(status: u64) -> Result<(), OsError> {
+    match status {
+        OK => Ok(()),
+        code => Err(OsError::from_code(code)),
+    }
+}
+
+/// Makes system call `$nr` with up to six arguments in `x0` to `x5`.
+/// Evaluates to `Ok((x0, x1))` if the status in `x7` is `0`, otherwise to the
+/// `OsError` of the status.
+#[macro_export]
+macro_rules! svc {
+    ($nr:expr $(, $a:expr)*) => {{
+        let (r0, r1, result) = svc_raw!($nr $(, $a)*);
+        result.map(|()| (r0, r1))
+    }};
+}
+
+/// Like `svc!`, but evaluates to `(x0, x1, Result)` so that results returned
+/// along with an error are not lost. Unused argument registers are zeroed.
+#[macro_export]
+macro_rules! svc_raw {
+    ($nr:expr) => { svc_raw!($nr, 0, 0, 0, 0, 0, 0) };
+    ($nr:expr, $a0:expr) => { svc_raw!($nr, $a0, 0, 0, 0, 0, 0) };
+    ($nr:expr, $a0:expr, $a1:expr) => { svc_raw!($nr, $a0, $a1, 0, 0, 0, 0) };
+    ($nr:expr, $a0:expr, $a1:expr, $a2:expr) => {
+        svc_raw!($nr, $a0, $a1, $a2, 0, 0, 0)
+    };
+    ($nr:expr, $a0:expr, $a1:expr, $a2:expr, $a3:expr) => {
+        svc_raw!($nr, $a0, $a1, $a2, $a3, 0, 0)
+    };
+    ($nr:expr, $a0:expr, $a1:expr, $a2:expr, $a3:expr, $a4:expr) => {
+        svc_raw!($nr, $a0, $a1, $a2, $a3, $a4, 0)
+    };
+    ($nr:expr, $a0:expr, $a1:expr, $a2:expr, $a3:expr, $a4:expr, $a5:expr) => {{
+        let r0: u64;
+        let r1: u64;
+        let status: u64;
+        asm!("mov x0, $3
+              mov x1, $4
+              mov x2, $5
+              mov x3, $6
+              mov x4, $7
+              mov x5, $8
+              svc $9
+              mov $0, x0
+              mov $1, x1
+              mov $2, x7"
+             : "=r"(r0), "=r"(r1), "=r"(status)
+             : "r"($a0 as u64), "r"($a1 as u64), "r"($a2 as u64),
+               "r"($a3 as u64), "r"($a4 as u64), "r"($a5 as u64), "i"($nr)
+             : "x0", "x1", "x2", "x3", "x4", "x5", "x7", "memory"
+             : "volatile");
+        (r0, r1, $crate::result(status))
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clock::Timespec;
+
+    #[test]
+    fn codes_round_trip() {
+        for code in 1..16 {
+            assert_eq!(OsError::from_code(code).code(), code);
+        }
+        assert_eq!(OsError::from_code(16), OsError::Unknown);
+    }
+
+    #[test]
+    fn status_round_trip() {
+        assert_eq!(status(Ok(())), OK);
+        assert_eq!(result(OK), Ok(()));
+        assert_eq!(result(status(Err(OsError::BrokenPipe))), Err(OsError::BrokenPipe));
+    }
+
+    #[test]
+    fn args_round_trip() {
+        let args: [&[u8]; 3] = [b"cat", b"", b"a b"];
+        let len = args::packed_len(args.iter().cloned());
+        assert_eq!(len, 9);
+
+        let mut buf = [0xff; 12];
+        assert_eq!(args::pack(args.iter().cloned(), &mut buf), len);
+        assert_eq!(&buf[..len], b"cat\0\0a b\0");
+        assert!(args::split(&buf[..len]).eq(args.iter().cloned()));
+    }
+
+    #[test]
+    fn args_without_trailing_nul() {
+        assert_eq!(args::split(b"").count(), 0);
+        assert!(args::split(b"sh\0-c").eq([&b"sh"[..]].iter().cloned()));
+    }
+
+    /// Returns a 32 byte binary with a header for `size` bytes.
+    fn program(size: u64) -> [u8; 32] {
+        let mut image = [0; 32];
+        image[4..8].copy_from_slice(&image::MAGIC);
+        for i in 0..8 {
+            image[8 + i] = (size >> (8 * i)) as u8;
+        }
+        image
+    }
+
+    #[test]
+    fn image_size() {
+        assert_eq!(image::mem_size(&program(0x1234)), Some(0x1234));
+        assert_eq!(image::mem_size(&program(32)), Some(32));
+        assert_eq!(image::mem_size(&program(31)), None);
+        assert_eq!(image::mem_size(&program(0x1234)[..image::HEADER_SIZE - 1]), None);
+
+        let mut bad = program(0x1234);
+        bad[4] = b'X';
+        assert_eq!(image::mem_size(&bad), None);
+    }
+
+    fn ts(secs: u64, nanos: u32) -> Timespec {
+        Timespec { secs, nanos }
+    }
+
+    #[test]
+    fn timespec_add() {
+        assert_eq!(ts(1, 500_000_000).checked_add(&ts(2, 400_000_000)), Some(ts(3, 900_000_000)));
+        assert_eq!(ts(1, 600_000_000).checked_add(&ts(0, 400_000_000)), Some(ts(2, 0)));
+        assert_eq!(ts(0, 999_999_999).checked_add(&ts(0, 999_999_999)), Some(ts(1, 999_999_998)));
+        assert_eq!(ts(!0, 600_000_000).checked_add(&ts(0, 400_000_000)), None);
+        assert_eq!(ts(!0, 0).checked_add(&ts(1, 0)), None);
+    }
+
+    #[test]
+    fn timespec_sub() {
+        assert_eq!(ts(3, 900_000_000).checked_sub(&ts(2, 400_000_000)), Some(ts(1, 500_000_000)));
+        assert_eq!(ts(2, 0).checked_sub(&ts(0, 1)), Some(ts(1, 999_999_999)));
+        assert_eq!(ts(2, 5).checked_sub(&ts(2, 5)), Some(ts(0, 0)));
+        assert_eq!(ts(2, 0).checked_sub(&ts(2, 1)), None);
+        assert_eq!(ts(1, 0).checked_sub(&ts(2, 0)), None);
+    }
+
+    /// Runs `clock::sleep(t)` against `results`, returned by the simulated
+    /// system call in turn, and returns the times it asked for.
+    fn sleeps(t: Timespec, results: &[Result<(), (OsError, Timespec)>]) -> [Option<Timespec>; 4] {
+        let mut asked = [None; 4];
+        let mut i = 0;
+        clock::sleep(t, |chunk| {
+            asked[i] = Some(chunk);
+            i += 1;
+            results[i - 1]
+        });
+        asked
+    }
+
+    #[test]
+    fn sleep_once() {
+        assert_eq!(sleeps(ts(1, 5), &[Ok(())]), [Some(ts(1, 5)), None, None, None]);
+        assert_eq!(sleeps(ts(0, 0), &[]), [None; 4]);
+    }
+
+    #[test]
+    fn sleep_retries_when_interrupted() {
+        let rem = ts(0, 300);
+        assert_eq!(sleeps(ts(1, 5), &[Err((OsError::Interrupted, rem)), Ok(())]),
+                   [Some(ts(1, 5)), Some(rem), None, None]);
+        assert_eq!(sleeps(ts(1, 5), &[Err((OsError::InvalidArgument, rem))]),
+                   [Some(ts(1, 5)), None, None, None]);
+    }
+
+    #[test]
+    fn sleep_in_chunks() {
+        let long = ts(clock::MAX_SLEEP_SECS * 2 + 1, 7);
+        let rem = ts(3, 0);
+        assert_eq!(sleeps(long, &[Ok(()), Err((OsError::Interrupted, rem)), Ok(()), Ok(())]),
+                   [Some(ts(clock::MAX_SLEEP_SECS, 7)), Some(ts(clock::MAX_SLEEP_SECS, 0)),
+                    Some(ts(4, 0)), None]);
+    }
+}
@@ -0,0 +1,39 @@
+#!/bin/sh
+# Prints the assembly of the kernel symbol table for the kernel ELF "$1": the
+# functions of its code sorted by address, as read by src/backtrace/symbols.rs.
+#
+# Each entry is the address of a function, the address of its name in
+# .ksyms.str and the length of the name.
+#
+# usage: NM=aarch64-none-elf-nm ksyms.sh kernel.elf > ksyms.S
+
+NM=${NM:-aarch64-none-elf-nm}
+
+set -e
+
+echo '    .section .ksyms, "a"'
+echo '    .balign 8'
+
+LC_ALL=C "$NM" -n -C --defined-only "$1" | LC_ALL=C awk '
+    $2 == "t" || $2 == "T" {
+        name = $0
+        sub(/^[0-9a-fA-F]+ [tT] /, "", name)
+        # mapping and local symbols are not functions
+        if (name ~ /^\$/ || name ~ /^\.L/) {
+            next
+        }
+        printf "    .quad 0x%s, .Lksym%d, %d\n", $1, n, length(name)
+        escaped = ""
+        for (i = 1; i <= length(name); i++) {
+            c = substr(name, i, 1)
+            escaped = escaped (c == "\\" || c == "\"" ? "\\" : "") c
+        }
+        names[n++] = escaped
+    }
+    END {
+        print "    .section .ksyms.str, \"a\""
+        for (i = 0; i < n; i++) {
+            printf ".Lksym%d: .ascii \"%s\"\n", i, names[i]
+        }
+    }
+'
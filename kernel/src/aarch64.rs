@@ -9,6 +9,78 @@ pub fn sp() -> *const u8 {
     ptr as *const u8
 }
 
+/// Returns the link register. Called first thing in an `#[inline(never)]`
+/// function, this is the address the function returns to in its caller.
+#[cfg(not(test))]
+#[inline(always)]
+pub fn lr() -> usize {
+    let ptr: usize;
+    unsafe {
+        asm!("mov $0, x30" : "=r"(ptr));
+    }
+
+    ptr
+}
+
+#[cfg(test)]
+pub fn lr() -> usize {
+    0
+}
+
+/// Returns the frame pointer, `x29`. Inlined, this points to the frame record
+/// of the calling function.
+#[cfg(not(test))]
+#[inline(always)]
+pub fn fp() -> usize {
+    let fp: usize;
+    unsafe {
+        asm!("mov $0, x29" : "=r"(fp));
+    }
+
+    fp
+}
+
+#[cfg(test)]
+pub fn fp() -> usize {
+    0
+}
+
+/// Returns the address of the instruction reading it. Inlined, this is an
+/// address in the calling function.
+#[cfg(not(test))]
+#[inline(always)]
+pub fn pc() -> usize {
+    let pc: usize;
+    unsafe {
+        asm!("adr $0, ." : "=r"(pc));
+    }
+
+    pc
+}
+
+#[cfg(test)]
+pub fn pc() -> usize {
+    0
+}
+
+/// Returns the fault address register, the virtual address that caused the
+/// last abort or alignment fault taken to EL1.
+#[cfg(not(test))]
+#[inline(always)]
+pub fn far() -> u64 {
+    let far: u64;
+    unsafe {
+        asm!("mrs $0, FAR_EL1" : "=r"(far));
+    }
+
+    far
+}
+
+#[cfg(test)]
+pub fn far() -> u64 {
+    0
+}
+
 /// Returns the current exception level.
 ///
 /// # Safety
@@ -57,3 +129,51 @@ pub fn wfi() {
         asm!("wfi" :::: "volatile");
     }
 }
+
+/// Invalidates the instruction cache of every core so that code written as
+/// data is fetched from memory.
+#[cfg(not(test))]
+pub fn sync_icache() {
+    unsafe {
+        asm!("dsb ish
+              ic ialluis
+              dsb ish
+              isb" :::: "volatile");
+    }
+}
+
+#[cfg(test)]
+pub fn sync_icache() { }
+
+/// Masks IRQs on the current core by setting `DAIF.I`. Returns the previous
+/// value of `DAIF` to be passed to `irq_restore`.
+///
+/// EL1 only: `SCTLR_EL1.UMA` is clear, so EL0 traps on writes to `DAIF`.
+#[cfg(not(test))]
+#[inline(always)]
+pub fn irq_save() -> u64 {
+    let daif: u64;
+    unsafe {
+        asm!("mrs $0, DAIF
+              msr DAIFSet, #2"
+             : "=r"(daif) ::: "volatile");
+    }
+    daif
+}
+
+/// Restores `DAIF` to a value previously returned by `irq_save`.
+#[cfg(not(test))]
+#[inline(always)]
+pub fn irq_restore(daif: u64) {
+    unsafe {
+        asm!("msr DAIF, $0" :: "r"(daif) :: "volatile");
+    }
+}
+
+#[cfg(test)]
+pub fn irq_save() -> u64 {
+    0
+}
+
+#[cfg(test)]
+pub fn irq_restore(_daif: u64) { }
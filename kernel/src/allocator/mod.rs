@@ -1,5 +1,5 @@
 mod linked_list;
-mod util;
+pub mod util;
 
 // #[path = "bump.rs"]
 // #[path = "bin.rs"]
@@ -9,13 +9,13 @@ mod imp;
 #[cfg(test)]
 mod tests;
 
-use mutex::Mutex;
+use mutex::IrqMutex;
 use alloc::heap::{Alloc, AllocErr, Layout};
 use std::cmp::max;
 
 /// Thread-safe (locking) wrapper around a particular memory allocator.
 #[derive(Debug)]
-pub struct Allocator(Mutex<Option<imp::Allocator>>);
+pub struct Allocator(IrqMutex<Option<imp::Allocator>>);
 
 impl Allocator {
     /// Returns an uninitialized `Allocator`.
@@ -23,7 +23,7 @@ impl Allocator {
     /// The allocator must be initialized by calling `initialize()` before the
     /// first memory allocation. Failure to do will result in panics.
     pub const fn uninitialized() -> Self {
-        Allocator(Mutex::new(None))
+        Allocator(IrqMutex::new(None))
     }
 
     /// Initializes the memory allocator.
@@ -0,0 +1,140 @@
+//! Register dumps and stack backtraces for kernel panics and fatal
+//! exceptions.
+//!
+//! The kernel is compiled with frame pointers (`eliminate-frame-pointer` is
+//! off in the target specification). Every function with a frame stores a
+//! frame record of two words, the frame pointer of its caller followed by its
+//! return address, and points `x29` at it. Following the chain of records
+//! from `x29` yields the return address of every active call, which are
+//! symbolized against the symbol table linked into the image (see `Symbol`).
+
+mod symbols;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use pi::common::IO_BASE;
+use console::{kprint, kprintln};
+use traps::TrapFrame;
+
+pub use self::symbols::{Symbol, lookup};
+#[cfg(not(test))]
+pub use self::symbols::{table, resolve};
+
+/// Maximum number of frames printed in a backtrace.
+pub const MAX_FRAMES: usize = 32;
+
+/// Set while a backtrace is printed, so that a fault or a panic while walking
+/// a corrupted stack does not print another one.
+static PRINTING: AtomicBool = AtomicBool::new( false );
+
+/// An iterator over the return addresses of the frame records chained from a
+/// frame pointer, innermost first.
+pub struct Frames {
+    fp: usize,
+    limit: usize,
+    depth: usize,
+}
+
+/// Returns the frames chained from the frame pointer `fp`. A record is only
+/// read if it is aligned to 16 bytes and below `limit`, and each caller's
+/// record must be above the previous one, as the stack grows down. The walk
+/// stops at a null frame pointer, a record that breaks these rules or after
+/// `MAX_FRAMES` frames.
+///
+/// # Safety
+///
+/// Memory below `limit` must be readable.
+pub unsafe fn frames(fp: usize, limit: usize) -> Frames {
+    Frames { fp, limit, depth: 0 }
+}
+
+impl Iterator for Frames {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let fp = self.fp;
+        if fp == 0 || fp % 16 != 0 || fp.saturating_add( 16 ) > self.limit || self.depth == MAX_FRAMES {
+            return None
+        }
+
+        let record = fp as *const usize;
+        let ( caller_fp, lr ) = unsafe { ( *record, *record.offset( 1 ) ) };
+        if lr == 0 {
+            return None
+        }
+
+        //a caller's record that is not above this one ends the walk after
+        //this frame
+        self.fp = if caller_fp > fp { caller_fp } else { 0 };
+        self.depth += 1;
+        Some( lr )
+    }
+}
+
+/// Prints frame `i` at `addr` with its symbol, if any.
+fn print_frame(i: usize, addr: u64) {
+    #[cfg(not(test))]
+    {
+        //a return address follows the call: symbolize the call itself, which
+        //may be the last instruction of the function
+        if let Some( ( sym, off ) ) = resolve( addr.saturating_sub( 4 ) ) {
+            kprintln!( "  #{:<2} {:#018x} {}+{:#x}", i, addr, sym.name(), off + 4 );
+            return
+        }
+    }
+    kprintln!( "  #{:<2} {:#018x} ?", i, addr );
+}
+
+/// Prints a backtrace starting at the instruction `pc` of the function whose
+/// frame record `fp` points to. Does nothing if a backtrace is already being
+/// printed.
+pub fn print(pc: u64, fp: u64) {
+    if PRINTING.swap( true, Ordering::SeqCst ) {
+        return
+    }
+
+    kprintln!( "backtrace:" );
+    print_frame( 0, pc );
+    for ( i, lr ) in unsafe { frames( fp as usize, IO_BASE ) }.enumerate() {
+        print_frame( i + 1, lr as u64 );
+    }
+
+    PRINTING.store( false, Ordering::SeqCst );
+}
+
+/// Prints the registers of `tf`, along with the syndrome `esr` and fault
+/// address `far` of the exception that saved it.
+pub fn dump_registers(tf: &TrapFrame, esr: u32, far: u64) {
+    let x = |i: usize| match i {
+        0 => tf.x0,
+        30 => tf.x30,
+        i => tf.x1_x29[ i - 1 ],
+    };
+
+    for i in 0..31 {
+        kprint!( "x{:<2} {:#018x}", i, x( i ) );
+        kprint!( "{}", if i % 4 == 3 { "\n" } else { "  " } );
+    }
+    kprintln!( "sp  {:#018x}", tf.SP );
+    kprintln!( "elr {:#018x}  spsr {:#018x}  esr {:#010x}  far {:#018x}  process {}",
+               tf.ELR, tf.SPSR, esr, far, tf.TPIDR );
+}
+
+/// Prints the registers of `tf` and the backtrace of the code it was saved
+/// from: the faulting instruction at `ELR`, then the calls chained from its
+/// `x29`.
+pub fn dump(tf: &TrapFrame, esr: u32, far: u64) {
+    dump_registers( tf, esr, far );
+    print( tf.ELR, tf.x1_x29[ 28 ] );
+}
+
+/// Prints the backtrace of the calling function.
+#[inline(always)]
+pub fn print_here() {
+    use aarch64;
+
+    print( aarch64::pc() as u64, aarch64::fp() as u64 );
+}
@@ -0,0 +1,74 @@
+use std::{slice, str};
+
+/// An entry of the kernel symbol table: the start address of a function and
+/// its name.
+///
+/// The table is generated by `ext/ksyms.sh` from the symbols of the kernel
+/// linked without it, then linked into the `.ksyms` section of the image,
+/// sorted by address. Since `.ksyms` follows `.text` and `.rodata`, adding
+/// the table does not move any function.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Symbol {
+    addr: u64,
+    name: *const u8,
+    len: u64,
+}
+
+impl Symbol {
+    /// Returns a symbol named `name` starting at `addr`.
+    pub fn new(addr: u64, name: &'static str) -> Symbol {
+        Symbol { addr, name: name.as_ptr(), len: name.len() as u64 }
+    }
+
+    /// Returns the start address of the symbol.
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
+    /// Returns the (demangled) name of the symbol.
+    pub fn name(&self) -> &'static str {
+        unsafe { str::from_utf8_unchecked( slice::from_raw_parts( self.name, self.len as usize ) ) }
+    }
+}
+
+#[cfg(not(test))]
+extern "C" {
+    static __ksyms_start: Symbol;
+    static __ksyms_end: Symbol;
+    static _start: u8;
+    static _etext: u8;
+}
+
+/// Returns the symbol table linked into the kernel, empty if the kernel was
+/// linked without one.
+#[cfg(not(test))]
+pub fn table() -> &'static [Symbol] {
+    unsafe {
+        let start = &__ksyms_start as *const Symbol;
+        let end = &__ksyms_end as *const Symbol;
+        slice::from_raw_parts( start, ( end as usize - start as usize ) / ::std::mem::size_of::<Symbol>() )
+    }
+}
+
+/// Returns the symbol containing `addr` and the offset of `addr` in it, or
+/// `None` if `addr` is not in the kernel's code.
+#[cfg(not(test))]
+pub fn resolve(addr: u64) -> Option<(&'static Symbol, u64)> {
+    let ( start, end ) = unsafe { ( &_start as *const u8 as u64, &_etext as *const u8 as u64 ) };
+    if addr < start || addr >= end {
+        return None
+    }
+    lookup( table(), addr )
+}
+
+/// Returns the last symbol of the sorted `table` starting at or before
+/// `addr`, and the offset of `addr` in it.
+pub fn lookup(table: &[Symbol], addr: u64) -> Option<(&Symbol, u64)> {
+    let i = match table.binary_search_by_key( &addr, |s| s.addr ) {
+        Ok(i) => i,
+        Err(0) => return None,
+        Err(i) => i - 1,
+    };
+    Some( ( &table[i], addr - table[i].addr ) )
+}
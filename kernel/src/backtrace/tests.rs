@@ -0,0 +1,73 @@
+use backtrace::{frames, lookup, Symbol, MAX_FRAMES};
+
+fn table() -> Vec<Symbol> {
+    vec![
+        Symbol::new(0x80000, "_start"),
+        Symbol::new(0x80100, "kernel::kmain"),
+        Symbol::new(0x80400, "kernel::traps::handle_exception"),
+    ]
+}
+
+#[test]
+fn lookup_finds_containing_symbol() {
+    let table = table();
+
+    let (sym, off) = lookup(&table, 0x80000).unwrap();
+    assert_eq!((sym.name(), off), ("_start", 0));
+
+    let (sym, off) = lookup(&table, 0x80124).unwrap();
+    assert_eq!((sym.name(), off), ("kernel::kmain", 0x24));
+
+    let (sym, off) = lookup(&table, 0x90000).unwrap();
+    assert_eq!((sym.addr(), off), (0x80400, 0xfc00));
+}
+
+#[test]
+fn lookup_before_first_symbol() {
+    assert!(lookup(&table(), 0x7fffc).is_none());
+    assert!(lookup(&[], 0x80000).is_none());
+}
+
+/// A fake stack of `n` frame records chained upwards, each with return
+/// address `0x1000 + i`. Returns the stack and the frame pointer of the
+/// innermost record.
+fn stack(n: usize) -> (Vec<u128>, usize) {
+    //u128 keeps the records aligned to 16 bytes
+    let mut stack = vec![0u128; n + 1];
+    let base = stack.as_ptr() as usize;
+    for i in 0..n {
+        let caller_fp = if i + 1 < n { base + (i + 1) * 16 } else { 0 };
+        stack[i] = (caller_fp as u128) | (((0x1000 + i) as u128) << 64);
+    }
+    (stack, base)
+}
+
+#[test]
+fn walks_frame_records() {
+    let (_stack, fp) = stack(5);
+    let lrs: Vec<usize> = unsafe { frames(fp, !0) }.collect();
+    assert_eq!(lrs, vec![0x1000, 0x1001, 0x1002, 0x1003, 0x1004]);
+}
+
+#[test]
+fn stops_at_bad_records() {
+    assert_eq!(unsafe { frames(0, !0) }.count(), 0);
+    assert_eq!(unsafe { frames(0x1008, !0) }.count(), 0);
+
+    //a record that ends above the limit is not read
+    let (_stack, fp) = stack(3);
+    assert_eq!(unsafe { frames(fp, fp + 8) }.count(), 0);
+    assert_eq!(unsafe { frames(fp, fp + 16) }.count(), 1);
+
+    //a caller's record below the current one ends the walk
+    let (mut stack, fp) = stack(3);
+    stack[1] = (fp as u128) | (0x2000u128 << 64);
+    let lrs: Vec<usize> = unsafe { frames(fp, !0) }.collect();
+    assert_eq!(lrs, vec![0x1000, 0x2000]);
+}
+
+#[test]
+fn stops_after_max_frames() {
+    let (_stack, fp) = stack(MAX_FRAMES + 8);
+    assert_eq!(unsafe { frames(fp, !0) }.count(), MAX_FRAMES);
+}
@@ -0,0 +1,157 @@
+//! The monotonic clock, counting from boot, and the wall clock, counting from
+//! the Unix epoch.
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+
+use mutex::IrqMutex;
+use pi::timer;
+
+pub use abi::clock::{CLOCK_MONOTONIC, CLOCK_REALTIME};
+
+/// Microseconds between the Unix epoch and the time the monotonic clock
+/// started, modulo 2^64. Zero until the wall clock is set.
+static EPOCH_OFFSET: IrqMutex<u64> = IrqMutex::new(0);
+
+/// Returns the microseconds since boot, read from the BCM system timer.
+pub fn monotonic() -> u64 {
+    timer::current_time()
+}
+
+/// Returns the microseconds since the Unix epoch. Counts from the epoch at
+/// boot until the wall clock is set.
+pub fn realtime() -> u64 {
+    EPOCH_OFFSET.lock().wrapping_add( monotonic() )
+}
+
+/// Sets the wall clock to `us` microseconds since the Unix epoch.
+pub fn set_realtime(us: u64) {
+    *EPOCH_OFFSET.lock() = us.wrapping_sub( monotonic() );
+}
+
+/// Returns the time of `clock`, `CLOCK_MONOTONIC` or `CLOCK_REALTIME`, in
+/// microseconds, or `None` if there is no such clock.
+pub fn read(clock: u64) -> Option<u64> {
+    match clock {
+        CLOCK_MONOTONIC => Some( monotonic() ),
+        CLOCK_REALTIME => Some( realtime() ),
+        _ => None,
+    }
+}
+
+/// A UTC date and time with a resolution of seconds, from the year 1970 to
+/// `MAX_YEAR`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DateTime {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The last year a `DateTime` converts to Unix time, the last with four
+/// digits.
+pub const MAX_YEAR: u32 = 9999;
+
+/// Returns `true` if `year` is a leap year.
+fn is_leap(year: u32) -> bool {
+    ( year % 4 == 0 && year % 100 != 0 ) || year % 400 == 0
+}
+
+/// Returns the number of days of `month` in `year`.
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 => if is_leap( year ) { 29 } else { 28 },
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl DateTime {
+    /// Returns the date and time `secs` seconds after the Unix epoch.
+    pub fn from_unix(secs: u64) -> DateTime {
+        let mut days = secs / SECS_PER_DAY;
+        let rem = ( secs % SECS_PER_DAY ) as u32;
+
+        let mut year = 1970;
+        loop {
+            let n = if is_leap( year ) { 366 } else { 365 };
+            if days < n {
+                break
+            }
+            days -= n;
+            year += 1;
+        }
+
+        let mut month = 1;
+        while days >= days_in_month( year, month ) as u64 {
+            days -= days_in_month( year, month ) as u64;
+            month += 1;
+        }
+
+        DateTime {
+            year,
+            month,
+            day: days as u32 + 1,
+            hour: rem / 3600,
+            minute: rem / 60 % 60,
+            second: rem % 60,
+        }
+    }
+
+    /// Returns the seconds since the Unix epoch, or `None` if a field is out
+    /// of range.
+    pub fn to_unix(&self) -> Option<u64> {
+        if self.year < 1970 || self.year > MAX_YEAR || self.month < 1 || self.month > 12
+            || self.day < 1 || self.day > days_in_month( self.year, self.month )
+            || self.hour > 23 || self.minute > 59 || self.second > 59 {
+            return None
+        }
+
+        let mut days = 0u64;
+        for y in 1970..self.year {
+            days += if is_leap( y ) { 366 } else { 365 };
+        }
+        for m in 1..self.month {
+            days += days_in_month( self.year, m ) as u64;
+        }
+        days += ( self.day - 1 ) as u64;
+
+        let secs = self.hour * 3600 + self.minute * 60 + self.second;
+        Some( days * SECS_PER_DAY + secs as u64 )
+    }
+
+    /// Parses a date `YYYY-MM-DD` and a time `HH:MM:SS`. Returns `None` if
+    /// either is malformed or out of range.
+    pub fn parse(date: &str, time: &str) -> Option<DateTime> {
+        use std::str::FromStr;
+
+        let mut d = date.split( '-' ).map( u32::from_str );
+        let mut t = time.split( ':' ).map( u32::from_str );
+        let dt = DateTime {
+            year: d.next()?.ok()?,
+            month: d.next()?.ok()?,
+            day: d.next()?.ok()?,
+            hour: t.next()?.ok()?,
+            minute: t.next()?.ok()?,
+            second: t.next()?.ok()?,
+        };
+        if d.next().is_some() || t.next().is_some() {
+            return None
+        }
+        dt.to_unix().map( |_| dt )
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!( f, "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+                self.year, self.month, self.day, self.hour, self.minute, self.second )
+    }
+}
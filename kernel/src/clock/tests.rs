@@ -0,0 +1,48 @@
+use clock::DateTime;
+
+fn date(year: u32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> DateTime {
+    DateTime { year, month, day, hour, minute, second }
+}
+
+#[test]
+fn epoch() {
+    assert_eq!(DateTime::from_unix(0), date(1970, 1, 1, 0, 0, 0));
+    assert_eq!(date(1970, 1, 1, 0, 0, 0).to_unix(), Some(0));
+}
+
+#[test]
+fn known_dates() {
+    assert_eq!(DateTime::from_unix(951_782_400), date(2000, 2, 29, 0, 0, 0));
+    assert_eq!(DateTime::from_unix(1_234_567_890), date(2009, 2, 13, 23, 31, 30));
+    assert_eq!(date(2038, 1, 19, 3, 14, 8).to_unix(), Some(1 << 31));
+    assert_eq!(date(9999, 12, 31, 23, 59, 59).to_unix(), Some(253_402_300_799));
+}
+
+#[test]
+fn round_trip() {
+    let mut secs = 0;
+    while secs < 4_000_000_000 {
+        assert_eq!(DateTime::from_unix(secs).to_unix(), Some(secs));
+        secs += 86_399 * 7 + 13;
+    }
+}
+
+#[test]
+fn out_of_range() {
+    assert_eq!(date(1969, 12, 31, 0, 0, 0).to_unix(), None);
+    assert_eq!(date(2001, 2, 29, 0, 0, 0).to_unix(), None);
+    assert_eq!(date(2000, 13, 1, 0, 0, 0).to_unix(), None);
+    assert_eq!(date(2000, 1, 1, 24, 0, 0).to_unix(), None);
+    assert_eq!(date(10000, 1, 1, 0, 0, 0).to_unix(), None);
+    assert_eq!(date(u32::max_value(), 1, 1, 0, 0, 0).to_unix(), None);
+}
+
+#[test]
+fn parse() {
+    assert_eq!(DateTime::parse("2018-03-04", "05:06:07"), Some(date(2018, 3, 4, 5, 6, 7)));
+    assert_eq!(DateTime::parse("2018-03", "05:06:07"), None);
+    assert_eq!(DateTime::parse("2018-03-04", "05:06:07:08"), None);
+    assert_eq!(DateTime::parse("2018-02-30", "05:06:07"), None);
+    assert_eq!(DateTime::parse("today", "now"), None);
+    assert_eq!(DateTime::parse("4294967295-01-01", "00:00:00"), None);
+}
@@ -221,15 +221,17 @@ pub struct CmdSleep {}
 impl<'a, 'b> ShellCmd<'a,'b> for CmdSleep {
     fn execute( fs: & fs::FileSystem, fs_path: & mut path::PathBuf, arg0: &'a str, args: &[ &'a str ] ) {
         use std::str::FromStr;
-        use traps;
+        use kthread;
         
         let n = args.len();
         if let Some(x) = args.iter().take(1).next() {
             match u32::from_str(x) {
                 Ok(t_ms) => {
                     kprintln!( "start sleep" );
-                    traps::syscall_sleep_ms( t_ms );
-                    kprintln!( "end sleep" );                  
+                    match kthread::sleep( t_ms ) {
+                        Ok(_) => kprintln!( "end sleep" ),
+                        Err(e) => kprintln!( "Err: sleep: {}", e ),
+                    }
                 },
                 _ => {
                     kprintln!( "Err: expected format: sleep <t_ms>" );
@@ -240,3 +242,226 @@ impl<'a, 'b> ShellCmd<'a,'b> for CmdSleep {
         }
     }
 }
+
+///ps command
+pub struct CmdPs {}
+impl<'a, 'b> ShellCmd<'a,'b> for CmdPs {
+    fn execute( fs: & fs::FileSystem, fs_path: & mut path::PathBuf, arg0: &'a str, args: &[ &'a str ] ) {
+        use SCHEDULER;
+
+        kprintln!( "{:>4} {:>4} {:<16} {:<14} {:>4} {:>10} {:>10} {:>8}", "id", "ppid", "name", "state", "core", "stack used", "stack size", "cpu ms" );
+        for p in SCHEDULER.snapshot().iter() {
+            let core = match p.core {
+                Some(c) => format!( "{}", c ),
+                None => String::from( "-" ),
+            };
+            let parent = match p.parent {
+                Some(x) => format!( "{}", x ),
+                None => String::from( "-" ),
+            };
+            kprintln!( "{:>4} {:>4} {:<16} {:<14} {:>4} {:>10} {:>10} {:>8}", p.id, parent, p.name, p.state, core, p.stack_used, p.stack_size, p.cpu_time );
+        }
+    }
+}
+
+///fg command
+pub struct CmdFg {}
+impl<'a, 'b> ShellCmd<'a,'b> for CmdFg {
+    fn execute( fs: & fs::FileSystem, fs_path: & mut path::PathBuf, arg0: &'a str, args: &[ &'a str ] ) {
+        use std::str::FromStr;
+        use shell::FOREGROUND;
+        use SCHEDULER;
+
+        match args.iter().next() {
+            None => {
+                match *FOREGROUND.lock() {
+                    Some(id) => kprintln!( "foreground process: {}", id ),
+                    None => kprintln!( "no foreground process" ),
+                }
+            },
+            Some(&"none") => {
+                *FOREGROUND.lock() = None;
+            },
+            Some(x) => {
+                match u64::from_str(x) {
+                    Ok(id) if SCHEDULER.with_process( id, |_| () ).is_some() => {
+                        *FOREGROUND.lock() = Some( id );
+                    },
+                    Ok(id) => {
+                        kprintln!( "Err: no process with id {}", id );
+                    },
+                    _ => {
+                        kprintln!( "Err: expected format: fg [<pid>|none]" );
+                    }
+                }
+            },
+        }
+    }
+}
+
+///affinity command
+pub struct CmdAffinity {}
+impl<'a, 'b> ShellCmd<'a,'b> for CmdAffinity {
+    fn execute( fs: & fs::FileSystem, fs_path: & mut path::PathBuf, arg0: &'a str, args: &[ &'a str ] ) {
+        use std::str::FromStr;
+        use process::AffinityError;
+        use smp;
+        use SCHEDULER;
+
+        let usage = "Err: expected format: affinity <pid> [<mask>]";
+
+        let id = match args.iter().next().map( |x| u64::from_str(x) ) {
+            Some(Ok(id)) => id,
+            _ => {
+                kprintln!( "{}", usage );
+                return
+            },
+        };
+
+        match args.get(1) {
+            None => {
+                match SCHEDULER.with_process( id, |p| p.affinity ) {
+                    Some(mask) => kprintln!( "process {} affinity: {:#x}", id, mask ),
+                    None => kprintln!( "Err: no process with id {}", id ),
+                }
+            },
+            Some(x) => {
+                let mask = if x.starts_with( "0x" ) {
+                    usize::from_str_radix( &x[2..], 16 )
+                } else {
+                    usize::from_str( x )
+                };
+                let mask = match mask {
+                    Ok(m) => m,
+                    _ => {
+                        kprintln!( "{}", usage );
+                        return
+                    },
+                };
+                match SCHEDULER.set_affinity( id, mask ) {
+                    Ok(old) => kprintln!( "process {} affinity: {:#x} -> {:#x}", id, old, mask ),
+                    Err(AffinityError::NoSuchProcess) => kprintln!( "Err: no process with id {}", id ),
+                    Err(AffinityError::NoOnlineCore) => {
+                        kprintln!( "Err: mask {:#x} contains no online core (online cores: {:#x})", mask, smp::online() );
+                    },
+                    Err(AffinityError::Idle) => kprintln!( "Err: process {} is an idle process pinned to its core", id ),
+                }
+            },
+        }
+    }
+}
+
+pub struct CmdStrace {}
+impl<'a, 'b> ShellCmd<'a,'b> for CmdStrace {
+    fn execute( fs: & fs::FileSystem, fs_path: & mut path::PathBuf, arg0: &'a str, args: &[ &'a str ] ) {
+        use std::str::FromStr;
+        use SCHEDULER;
+
+        let usage = "Err: expected format: strace <pid> on|off";
+
+        let id = match args.iter().next().map( |x| u64::from_str(x) ) {
+            Some(Ok(id)) => id,
+            _ => {
+                kprintln!( "{}", usage );
+                return
+            },
+        };
+
+        let on = match args.get(1) {
+            Some(&"on") => true,
+            Some(&"off") => false,
+            _ => {
+                kprintln!( "{}", usage );
+                return
+            },
+        };
+
+        match SCHEDULER.with_process( id, |p| p.traced = on ) {
+            Some(()) => kprintln!( "process {} tracing {}", id, if on { "on" } else { "off" } ),
+            None => kprintln!( "Err: no process with id {}", id ),
+        }
+    }
+}
+
+pub struct CmdDate {}
+impl<'a, 'b> ShellCmd<'a,'b> for CmdDate {
+    fn execute( fs: & fs::FileSystem, fs_path: & mut path::PathBuf, arg0: &'a str, args: &[ &'a str ] ) {
+        use clock::{self, DateTime};
+
+        match args.len() {
+            0 => {
+                kprintln!( "{}", DateTime::from_unix( clock::realtime() / 1_000_000 ) );
+            },
+            2 => {
+                let secs = DateTime::parse( args[0], args[1] ).and_then( |x| x.to_unix() );
+                match secs.and_then( |s| s.checked_mul( 1_000_000 ).map( |us| ( s, us ) ) ) {
+                    Some(( secs, us )) => {
+                        clock::set_realtime( us );
+                        kprintln!( "{}", DateTime::from_unix( secs ) );
+                    },
+                    None => kprintln!( "Err: invalid date or time" ),
+                }
+            },
+            _ => kprintln!( "Err: expected format: date [<YYYY-MM-DD> <HH:MM:SS>]" ),
+        }
+    }
+}
+
+///run command
+pub struct CmdRun {}
+impl<'a, 'b> ShellCmd<'a,'b> for CmdRun {
+    fn execute( fs: & fs::FileSystem, fs_path: & mut path::PathBuf, arg0: &'a str, args: &[ &'a str ] ) {
+        use fat32::traits::Entry;
+        use std::io::Read;
+        use process;
+        use shell::FOREGROUND;
+        use SCHEDULER;
+
+        let name = match args.iter().next() {
+            Some(x) => *x,
+            None => {
+                kprintln!( "Err: expected format: run <path> [args...]" );
+                return
+            },
+        };
+
+        let image = match get_entry_from_path( fs, & fs_path, & path::PathBuf::from( name ) ) {
+            Ok( ( entry, _ ) ) => {
+                match entry.into_file() {
+                    Some( mut f ) => {
+                        let mut temp = vec![ 0u8; f.size ];
+                        if let Err(e) = f.read_exact( & mut temp[..] ) {
+                            kprintln!( "Err: {}", e );
+                            return
+                        }
+                        temp
+                    },
+                    None => {
+                        kprintln!( "Err: path is not a file" );
+                        return
+                    },
+                }
+            },
+            Err( e ) => {
+                kprintln!( "Err: {}", e );
+                return
+            },
+        };
+
+        let p = match process::load( & image, name, & args[1..] ) {
+            Ok(p) => p,
+            Err(e) => {
+                kprintln!( "Err: {}: {}", name, e );
+                return
+            },
+        };
+
+        match SCHEDULER.add( p ) {
+            Some(id) => {
+                *FOREGROUND.lock() = Some( id );
+                kprintln!( "process {} started in the foreground", id );
+            },
+            None => kprintln!( "Err: {}: too many processes", name ),
+        }
+    }
+}
@@ -3,17 +3,26 @@ use std::fmt;
 
 use pi::uart::MiniUart;
 
-use mutex::Mutex;
+use mutex::IrqMutex;
+use process::WaitQueue;
+use traps::TrapFrame;
+
+/// Capacity of the buffer of received bytes not read yet.
+const RX_SIZE: usize = 256;
 
 /// A global singleton allowing read/write access to the console.
 pub struct Console {
-    inner: Option<MiniUart>
+    inner: Option<MiniUart>,
+    /// Ring buffer of the bytes drained from the UART by the receive interrupt.
+    rx: [u8; RX_SIZE],
+    rx_head: usize,
+    rx_len: usize,
 }
 
 impl Console {
     /// Creates a new instance of `Console`.
     const fn new() -> Console {
-        Console { inner: None }
+        Console { inner: None, rx: [0; RX_SIZE], rx_head: 0, rx_len: 0 }
     }
 
     /// Initializes the console if it's not already initialized.
@@ -34,10 +43,39 @@ impl Console {
         self.inner.as_mut().unwrap()
     }
 
+    /// Moves the bytes received by the UART into the receive buffer, which
+    /// clears the receive interrupt. Bytes are dropped once the buffer is full.
+    fn receive(&mut self) {
+        while self.inner().has_byte() {
+            let byte = self.inner().read_byte();
+            if self.rx_len < RX_SIZE {
+                self.rx[ ( self.rx_head + self.rx_len ) % RX_SIZE ] = byte;
+                self.rx_len += 1;
+            }
+        }
+    }
+
+    /// Enables or disables the UART receive interrupt, handled by
+    /// `handle_rx`.
+    pub fn set_rx_interrupt(&mut self, enable: bool) {
+        self.inner().set_rx_interrupt( enable );
+    }
+
     /// Reads a byte from the UART device, blocking until a byte is available.
     pub fn read_byte(&mut self) -> u8 {
-        let mut buf = [0];
-        self.inner().read_byte()
+        if self.rx_len == 0 {
+            return self.inner().read_byte()
+        }
+        let byte = self.rx[ self.rx_head ];
+        self.rx_head = ( self.rx_head + 1 ) % RX_SIZE;
+        self.rx_len -= 1;
+        byte
+    }
+
+    /// Returns `true` if there is at least one byte ready to be read. This
+    /// method does not block.
+    pub fn has_byte(&mut self) -> bool {
+        self.rx_len > 0 || self.inner().has_byte()
     }
 
     /// Writes the byte `byte` to the UART device.
@@ -48,7 +86,16 @@ impl Console {
 
 impl io::Read for Console {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        <MiniUart as io::Read >::read( self.inner(), buf )
+        if buf.is_empty() {
+            return Ok(0)
+        }
+        buf[0] = self.read_byte();
+        let mut n = 1;
+        while n < buf.len() && self.has_byte() {
+            buf[n] = self.read_byte();
+            n += 1;
+        }
+        Ok(n)
     }
 }
 
@@ -69,7 +116,17 @@ impl fmt::Write for Console {
 }
 
 /// Global `Console` singleton.
-pub static CONSOLE: Mutex<Console> = Mutex::new(Console::new());
+pub static CONSOLE: IrqMutex<Console> = IrqMutex::new(Console::new());
+
+/// Processes blocked until the console receives data. Woken by the UART IRQ.
+pub static CONSOLE_RX: WaitQueue = WaitQueue::new();
+
+/// Handles the mini UART's `Aux` interrupt: buffers the received bytes and
+/// wakes up readers blocked on console input.
+pub fn handle_rx(_tf: &mut TrapFrame) {
+    CONSOLE.lock().receive();
+    CONSOLE_RX.wake_all();
+}
 
 /// Internal function called by the `kprint[ln]!` macros.
 #[doc(hidden)]
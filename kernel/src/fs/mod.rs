@@ -6,10 +6,10 @@ use std::path::Path;
 use fat32::vfat::{self, Shared, VFat};
 pub use fat32::traits;
 
-use mutex::Mutex;
+use mutex::IrqMutex;
 use self::sd::Sd;
 
-pub struct FileSystem(Mutex<Option<Shared<VFat>>>);
+pub struct FileSystem(IrqMutex<Option<Shared<VFat>>>);
 
 impl FileSystem {
     /// Returns an uninitialized `FileSystem`.
@@ -17,7 +17,7 @@ impl FileSystem {
     /// The file system must be initialized by calling `initialize()` before the
     /// first memory allocation. Failure to do will result in panics.
     pub const fn uninitialized() -> Self {
-        FileSystem(Mutex::new(None))
+        FileSystem(IrqMutex::new(None))
     }
 
     /// Initializes the file system.
@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+
+use abi;
+use process::Id;
+
+/// Size in bytes of the payload of a message.
+pub const MSG_SIZE: usize = abi::MSG_SIZE;
+
+/// Number of messages a mailbox holds before `send` to it fails.
+pub const MAILBOX_SIZE: usize = 16;
+
+/// A fixed-size message sent between processes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Message {
+    /// ID of the sending process.
+    pub sender: Id,
+    pub data: [u8; MSG_SIZE],
+}
+
+/// The queue of messages sent to a process and not yet received, in the order
+/// they were sent.
+#[derive(Debug)]
+pub struct Mailbox(VecDeque<Message>);
+
+impl Mailbox {
+    /// Returns an empty mailbox.
+    pub fn new() -> Mailbox {
+        Mailbox(VecDeque::new())
+    }
+
+    /// Queues `msg`. Returns it back if the mailbox already holds
+    /// `MAILBOX_SIZE` messages.
+    pub fn push(&mut self, msg: Message) -> Result<(), Message> {
+        if self.0.len() == MAILBOX_SIZE {
+            return Err(msg)
+        }
+        self.0.push_back(msg);
+        Ok(())
+    }
+
+    /// Removes and returns the oldest message.
+    pub fn pop(&mut self) -> Option<Message> {
+        self.0.pop_front()
+    }
+
+    /// Returns the number of queued messages.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
@@ -0,0 +1,10 @@
+mod pipe;
+mod message;
+mod shm;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::pipe::{Pipe, Transfer, PIPE_SIZE};
+pub use self::message::{Message, Mailbox, MSG_SIZE, MAILBOX_SIZE};
+pub use self::shm::{Region, SharedMemory, ShmError, PAGE_SIZE};
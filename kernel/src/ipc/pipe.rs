@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+
+use mutex::IrqMutex;
+use sync::{Condvar, Sched, KernelSched};
+use traps::TrapFrame;
+
+/// Number of bytes a pipe buffers before writers block.
+pub const PIPE_SIZE: usize = 512;
+
+/// Outcome of a read from or a write to a pipe.
+#[derive(Debug, PartialEq)]
+pub enum Transfer {
+    /// This many bytes were transferred. A read of `0` bytes is end-of-file.
+    Done(usize),
+    /// The calling process was blocked and `tf` now belongs to another
+    /// process. The call should be retried once the process is woken up.
+    Blocked,
+    /// Written to a pipe without readers.
+    Broken,
+}
+
+#[derive(Debug)]
+struct Inner {
+    buf: Option<VecDeque<u8>>,
+    readers: usize,
+    writers: usize,
+}
+
+/// A unidirectional byte stream between processes, backed by a ring buffer of
+/// `PIPE_SIZE` bytes.
+///
+/// Reading from an empty pipe blocks until data is written or the last writer
+/// closes its end, after which reads return end-of-file. Writing to a full
+/// pipe blocks until data is read. A new pipe has one reader and one writer.
+#[derive(Debug)]
+pub struct Pipe<S = KernelSched> {
+    inner: IrqMutex<Inner>,
+    readable: Condvar<S>,
+    writable: Condvar<S>,
+}
+
+impl<S> Pipe<S> {
+    /// Returns an empty pipe with one open read end and one open write end.
+    pub const fn new() -> Pipe<S> {
+        Pipe {
+            inner: IrqMutex::new(Inner { buf: None, readers: 1, writers: 1 }),
+            readable: Condvar::new(),
+            writable: Condvar::new(),
+        }
+    }
+}
+
+impl<S: Sched> Pipe<S> {
+    /// Reads up to `buf.len()` bytes for the process executing in `tf`. Blocks
+    /// the process if the pipe is empty and a write end is still open.
+    pub fn read(&self, buf: &mut [u8], tf: &mut TrapFrame) -> Transfer {
+        let mut inner = self.inner.lock();
+        let n = {
+            let data = inner.buf.get_or_insert_with(|| VecDeque::with_capacity(PIPE_SIZE));
+            let n = data.len().min( buf.len() );
+            for (dst, src) in buf.iter_mut().zip( data.drain(..n) ) {
+                *dst = src;
+            }
+            n
+        };
+
+        if n > 0 || buf.is_empty() {
+            self.writable.notify_all();
+            return Transfer::Done(n)
+        }
+        if inner.writers == 0 {
+            return Transfer::Done(0)
+        }
+
+        self.readable.wait( inner, tf );
+        Transfer::Blocked
+    }
+
+    /// Writes up to `buf.len()` bytes for the process executing in `tf`.
+    /// Blocks the process if the pipe is full and a read end is still open.
+    /// Writes larger than the free space are partial.
+    pub fn write(&self, buf: &[u8], tf: &mut TrapFrame) -> Transfer {
+        let mut inner = self.inner.lock();
+        if inner.readers == 0 {
+            return Transfer::Broken
+        }
+
+        let n = {
+            let data = inner.buf.get_or_insert_with(|| VecDeque::with_capacity(PIPE_SIZE));
+            let n = ( PIPE_SIZE - data.len() ).min( buf.len() );
+            data.extend( buf[..n].iter().cloned() );
+            n
+        };
+
+        if n > 0 || buf.is_empty() {
+            self.readable.notify_all();
+            return Transfer::Done(n)
+        }
+
+        self.writable.wait( inner, tf );
+        Transfer::Blocked
+    }
+
+    /// Closes one read end. Once every read end is closed, blocked writers
+    /// are woken up and writes fail with `Transfer::Broken`.
+    pub fn close_read(&self) {
+        let mut inner = self.inner.lock();
+        inner.readers -= 1;
+        if inner.readers == 0 {
+            self.writable.notify_all();
+        }
+    }
+
+    /// Closes one write end. Once every write end is closed, blocked readers
+    /// are woken up and reads of an empty pipe return end-of-file.
+    pub fn close_write(&self) {
+        let mut inner = self.inner.lock();
+        inner.writers -= 1;
+        if inner.writers == 0 {
+            self.readable.notify_all();
+        }
+    }
+
+    /// Returns the number of buffered bytes.
+    pub fn len(&self) -> usize {
+        self.inner.lock().buf.as_ref().map_or(0, |x| x.len())
+    }
+}
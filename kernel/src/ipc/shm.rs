@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ptr::Unique;
+use std::sync::{Arc, Weak};
+
+use abi;
+use alloc::allocator::{Alloc, Layout};
+use alloc::heap::Heap;
+use mutex::IrqMutex;
+use vm::PhysicalAddr;
+
+/// Size and alignment of the memory backing a shared memory region.
+pub const PAGE_SIZE: usize = abi::PAGE_SIZE;
+
+/// A named block of page-aligned memory shared between processes.
+///
+/// Processes hold an `Arc<Region>` for every mapping. The memory is released
+/// when the last mapping is dropped. The kernel runs without the MMU, so every
+/// process sees the region at the same address.
+pub struct Region {
+    name: String,
+    ptr: Unique<u8>,
+    size: usize,
+}
+
+impl Region {
+    /// The layout of a region of `size` bytes.
+    fn layout(size: usize) -> Layout {
+        unsafe { Layout::from_size_align_unchecked(size, PAGE_SIZE) }
+    }
+
+    /// Returns a newly allocated region named `name` of at least `size` bytes,
+    /// zeroed out. The size is rounded up to a multiple of `PAGE_SIZE`.
+    /// Returns `None` if the memory could not be allocated, including sizes
+    /// too large to be rounded up.
+    fn new(name: &str, size: usize) -> Option<Region> {
+        let size = size.max( PAGE_SIZE ).checked_add( PAGE_SIZE - 1 )? & !( PAGE_SIZE - 1 );
+
+        let raw_ptr = unsafe {
+            let raw_ptr: *mut u8 = Heap.alloc(Region::layout(size)).ok()?;
+            raw_ptr.write_bytes(0, size);
+            raw_ptr
+        };
+
+        let ptr = Unique::new(raw_ptr).expect("non-null");
+        Some(Region { name: String::from(name), ptr, size })
+    }
+
+    /// Returns a newly allocated unnamed region of at least `size` bytes,
+    /// zeroed out, for the private use of one process. The size is rounded up
+    /// to a multiple of `PAGE_SIZE`. Returns `None` if the memory could not be
+    /// allocated.
+    pub fn anonymous(size: usize) -> Option<Region> {
+        Region::new( "", size )
+    }
+
+    /// Returns the name of the region.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the physical address of the start of the region.
+    pub fn start(&self) -> PhysicalAddr {
+        self.ptr.as_ptr().into()
+    }
+
+    /// Returns the size of the region in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl Drop for Region {
+    fn drop(&mut self) {
+        unsafe {
+            Heap.dealloc(self.ptr.as_ptr(), Self::layout(self.size))
+        }
+    }
+}
+
+impl fmt::Debug for Region {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Region")
+            .field("name", &self.name)
+            .field("start", &self.start())
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+/// Errors creating a shared memory region.
+#[derive(Debug, PartialEq)]
+pub enum ShmError {
+    /// A region with the same name is still mapped.
+    Exists,
+    /// The memory could not be allocated.
+    NoMemory,
+}
+
+/// The registry of named shared memory regions.
+///
+/// The registry does not keep regions alive: a name is forgotten once every
+/// mapping of its region is dropped and can then be created again.
+#[derive(Debug)]
+pub struct SharedMemory(IrqMutex<Option<BTreeMap<String, Weak<Region>>>>);
+
+impl SharedMemory {
+    /// Returns an empty registry.
+    pub const fn new() -> SharedMemory {
+        SharedMemory(IrqMutex::new(None))
+    }
+
+    /// Creates a region named `name` of at least `size` bytes and returns the
+    /// first mapping of it.
+    pub fn create(&self, name: &str, size: usize) -> Result<Arc<Region>, ShmError> {
+        let mut guard = self.0.lock();
+        let regions = guard.get_or_insert_with(BTreeMap::new);
+
+        //forget regions whose last mapping was dropped
+        let dead: Vec<String> = regions.iter()
+            .filter( |&(_, r)| r.upgrade().is_none() )
+            .map( |(name, _)| name.clone() )
+            .collect();
+        for name in dead {
+            regions.remove( &name );
+        }
+
+        if regions.contains_key( name ) {
+            return Err( ShmError::Exists )
+        }
+
+        let region = Arc::new( Region::new( name, size ).ok_or( ShmError::NoMemory )? );
+        regions.insert( String::from( name ), Arc::downgrade( &region ) );
+        Ok( region )
+    }
+
+    /// Returns a new mapping of the region named `name`, or `None` if there
+    /// is no such region.
+    pub fn open(&self, name: &str) -> Option<Arc<Region>> {
+        self.0.lock().as_ref()?.get( name )?.upgrade()
+    }
+}
@@ -0,0 +1,223 @@
+mod pipe {
+    use ipc::{Pipe, Transfer, PIPE_SIZE};
+    use sync::sim::*;
+
+    #[test]
+    fn read_what_was_written() {
+        let pipe: Pipe<SimSched> = Pipe::new();
+        let mut t = tf(1);
+        let mut buf = [0u8; 8];
+
+        assert_eq!(pipe.write(b"hello", &mut t), Transfer::Done(5));
+        assert_eq!(pipe.len(), 5);
+        assert_eq!(pipe.read(&mut buf[..3], &mut t), Transfer::Done(3));
+        assert_eq!(&buf[..3], b"hel");
+        assert_eq!(pipe.read(&mut buf, &mut t), Transfer::Done(2));
+        assert_eq!(&buf[..2], b"lo");
+    }
+
+    #[test]
+    fn read_blocks_until_written() {
+        let pipe: Pipe<SimSched> = Pipe::new();
+        ready(&[2]);
+
+        let mut t = tf(1);
+        let mut buf = [0u8; 8];
+        assert_eq!(pipe.read(&mut buf, &mut t), Transfer::Blocked);
+        assert!(is_blocked(1));
+        assert_eq!(t.TPIDR, 2);
+
+        assert_eq!(pipe.write(b"x", &mut t), Transfer::Done(1));
+        assert!(!is_blocked(1));
+        assert_eq!(ready_queue(), vec![1]);
+    }
+
+    #[test]
+    fn write_blocks_when_full() {
+        let pipe: Pipe<SimSched> = Pipe::new();
+        ready(&[2]);
+
+        let mut t = tf(1);
+        let data = [7u8; PIPE_SIZE + 10];
+        assert_eq!(pipe.write(&data, &mut t), Transfer::Done(PIPE_SIZE));
+        assert_eq!(pipe.write(&data, &mut t), Transfer::Blocked);
+        assert!(is_blocked(1));
+
+        let mut buf = [0u8; 4];
+        assert_eq!(pipe.read(&mut buf, &mut t), Transfer::Done(4));
+        assert!(!is_blocked(1));
+        assert_eq!(pipe.len(), PIPE_SIZE - 4);
+    }
+
+    #[test]
+    fn eof_after_last_writer_closes() {
+        let pipe: Pipe<SimSched> = Pipe::new();
+        ready(&[2]);
+
+        let mut t = tf(1);
+        let mut buf = [0u8; 8];
+        assert_eq!(pipe.read(&mut buf, &mut t), Transfer::Blocked);
+
+        pipe.close_write();
+        assert_eq!(ready_queue(), vec![1]);
+        assert_eq!(pipe.read(&mut buf, &mut t), Transfer::Done(0));
+    }
+
+    #[test]
+    fn buffered_data_is_read_before_eof() {
+        let pipe: Pipe<SimSched> = Pipe::new();
+        let mut t = tf(1);
+        let mut buf = [0u8; 8];
+
+        assert_eq!(pipe.write(b"ab", &mut t), Transfer::Done(2));
+        pipe.close_write();
+        assert_eq!(pipe.read(&mut buf, &mut t), Transfer::Done(2));
+        assert_eq!(pipe.read(&mut buf, &mut t), Transfer::Done(0));
+    }
+
+    #[test]
+    fn write_without_readers() {
+        let pipe: Pipe<SimSched> = Pipe::new();
+        ready(&[2]);
+
+        let mut t = tf(1);
+        let data = [0u8; PIPE_SIZE];
+        assert_eq!(pipe.write(&data, &mut t), Transfer::Done(PIPE_SIZE));
+        assert_eq!(pipe.write(b"x", &mut t), Transfer::Blocked);
+
+        pipe.close_read();
+        assert_eq!(ready_queue(), vec![1]);
+        assert_eq!(pipe.write(b"x", &mut t), Transfer::Broken);
+    }
+
+    const PRODUCER: u64 = 1;
+    const CONSUMER: u64 = 2;
+
+    /// Runs a producer writing `data` in chunks of `wsize` bytes and a
+    /// consumer reading in chunks of `rsize` bytes until end-of-file, each
+    /// preempted after every `slice` successful calls. Returns what the
+    /// consumer read.
+    fn produce_consume(data: &[u8], wsize: usize, rsize: usize, slice: usize) -> Vec<u8> {
+        let pipe: Pipe<SimSched> = Pipe::new();
+        let mut received = Vec::new();
+        let mut sent = 0;
+        let mut calls = 0;
+
+        let mut t = tf(PRODUCER);
+        ready(&[CONSUMER]);
+
+        //a blocked call is retried when its process runs again, like a
+        //system call that rewound `ELR`
+        loop {
+            let transfer = match t.TPIDR {
+                PRODUCER if sent == data.len() => {
+                    pipe.close_write();
+                    exit(&mut t);
+                    continue
+                },
+                PRODUCER => {
+                    let end = data.len().min(sent + wsize);
+                    let r = pipe.write(&data[sent..end], &mut t);
+                    if let Transfer::Done(n) = r {
+                        sent += n;
+                    }
+                    r
+                },
+                CONSUMER => {
+                    let mut buf = vec![0u8; rsize];
+                    let r = pipe.read(&mut buf, &mut t);
+                    match r {
+                        Transfer::Done(0) => break,
+                        Transfer::Done(n) => received.extend_from_slice(&buf[..n]),
+                        _ => {},
+                    }
+                    r
+                },
+                id => panic!("process {} scheduled", id),
+            };
+
+            assert_ne!(transfer, Transfer::Broken);
+            if let Transfer::Done(_) = transfer {
+                calls += 1;
+                if calls % slice == 0 && !ready_queue().is_empty() {
+                    preempt(&mut t);
+                }
+            }
+        }
+
+        assert_eq!(t.TPIDR, CONSUMER);
+        assert!(ready_queue().is_empty());
+        received
+    }
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + i / 256) as u8).collect()
+    }
+
+    #[test]
+    fn producer_consumer() {
+        let data = data(PIPE_SIZE * 5 + 17);
+        assert_eq!(produce_consume(&data, 100, 37, 3), data);
+    }
+
+    #[test]
+    fn producer_consumer_large_writes() {
+        let data = data(PIPE_SIZE * 3);
+        assert_eq!(produce_consume(&data, PIPE_SIZE * 2, 64, 1), data);
+    }
+
+    #[test]
+    fn producer_consumer_small_reads() {
+        let data = data(PIPE_SIZE + 1);
+        assert_eq!(produce_consume(&data, 33, 1, 1000), data);
+    }
+}
+
+mod message {
+    use ipc::{Message, Mailbox, MSG_SIZE, MAILBOX_SIZE};
+
+    fn msg(sender: u64, byte: u8) -> Message {
+        Message { sender, data: [byte; MSG_SIZE] }
+    }
+
+    #[test]
+    fn fifo_order() {
+        let mut mailbox = Mailbox::new();
+        assert_eq!(mailbox.pop(), None);
+
+        mailbox.push(msg(1, 1)).unwrap();
+        mailbox.push(msg(2, 2)).unwrap();
+        mailbox.push(msg(1, 3)).unwrap();
+        assert_eq!(mailbox.len(), 3);
+
+        assert_eq!(mailbox.pop(), Some(msg(1, 1)));
+        assert_eq!(mailbox.pop(), Some(msg(2, 2)));
+        assert_eq!(mailbox.pop(), Some(msg(1, 3)));
+        assert_eq!(mailbox.pop(), None);
+    }
+
+    #[test]
+    fn full_mailbox_rejects() {
+        let mut mailbox = Mailbox::new();
+        for i in 0..MAILBOX_SIZE {
+            mailbox.push(msg(1, i as u8)).unwrap();
+        }
+
+        assert_eq!(mailbox.push(msg(2, 0xff)), Err(msg(2, 0xff)));
+        assert_eq!(mailbox.pop(), Some(msg(1, 0)));
+        assert!(mailbox.push(msg(2, 0xff)).is_ok());
+        assert_eq!(mailbox.len(), MAILBOX_SIZE);
+    }
+}
+
+mod shm {
+    use ipc::{SharedMemory, ShmError, PAGE_SIZE};
+
+    #[test]
+    fn size_overflow_is_no_memory() {
+        let shm = SharedMemory::new();
+        assert_eq!(shm.create("big", !0).unwrap_err(), ShmError::NoMemory);
+        assert_eq!(shm.create("big", !0 - PAGE_SIZE + 2).unwrap_err(), ShmError::NoMemory);
+        assert!(shm.open("big").is_none());
+    }
+}
@@ -26,10 +26,13 @@ extern crate alloc;
 extern crate pi;
 extern crate stack_vec;
 extern crate fat32;
+#[macro_use]
+extern crate abi;
 
 pub mod allocator;
 pub mod lang_items;
 pub mod mutex;
+pub mod lockdep;
 pub mod console;
 pub mod cmds;
 pub mod shell;
@@ -37,7 +40,13 @@ pub mod fs;
 pub mod traps;
 pub mod aarch64;
 pub mod process;
+pub mod kthread;
+pub mod smp;
+pub mod sync;
+pub mod ipc;
 pub mod vm;
+pub mod clock;
+pub mod backtrace;
 
 #[cfg(not(test))]
 use allocator::Allocator;
@@ -140,9 +149,21 @@ pub extern "C" fn kmain() {
 
     check_fs();
 
+    //wake console readers on mini UART receive interrupts
+    traps::irq::register( pi::interrupt::Interrupt::Aux, console::handle_rx );
+    console::CONSOLE.lock().set_rx_interrupt( true );
+
     SCHEDULER.start();
 }
 
+/// Entry point of the secondary cores once they are released by
+/// `smp::start_secondary_cores`.
+#[no_mangle]
+#[cfg(not(test))]
+pub extern "C" fn kmain_secondary() {
+    SCHEDULER.start_core();
+}
+
 #[no_mangle]
 #[cfg(not(test))]
 pub extern "C" fn func_shell() {
@@ -158,11 +179,11 @@ pub extern "C" fn func_shell() {
 
 #[no_mangle]
 #[cfg(not(test))]
-pub extern "C" fn func_periodic_print() {
+pub extern "C" fn func_periodic_print( _arg: u64 ) {
     use console::kprintln;
-    use traps;
+    use kthread;
     loop{
-        let t_actual = traps::syscall_sleep_ms( 7000 );
+        let _ = kthread::sleep( 7000 );
         kprintln!( "periodic print.." );
     }
 }
@@ -0,0 +1,72 @@
+use abi::{nr, OsError};
+use process::{Process, Id, Stack};
+use SCHEDULER;
+
+/// Type of the entry point of a kernel thread. The argument passed to `spawn`
+/// is received as the only parameter.
+pub type ThreadFn = extern "C" fn(u64);
+
+/// Spawns a kernel thread named `name` that executes `f(arg)` at EL1 on its
+/// own stack of the default size. Returns the ID of the new process, or `None`
+/// if it could not be allocated or scheduled.
+///
+/// The thread is preempted like any other process. When `f` returns, the
+/// thread exits and its resources are released.
+///
+/// # Panics
+///
+/// Panics if the scheduler has not been initialized.
+pub fn spawn(f: ThreadFn, arg: u64, name: &str) -> Option<Id> {
+    spawn_with_stack( f, arg, name, Stack::SIZE )
+}
+
+/// Like `spawn`, but the thread runs on a stack of at least `stack_size`
+/// bytes, rounded up to a multiple of `Stack::ALIGN`.
+///
+/// # Panics
+///
+/// Panics if the scheduler has not been initialized.
+pub fn spawn_with_stack(f: ThreadFn, arg: u64, name: &str, stack_size: usize) -> Option<Id> {
+    SCHEDULER.add( process( f, arg, name, stack_size )? )
+}
+
+/// Creates, but does not schedule, the process of a kernel thread executing
+/// `f(arg)` on a stack of at least `stack_size` bytes. Returns `None` if the
+/// process could not be allocated.
+pub fn process(f: ThreadFn, arg: u64, name: &str, stack_size: usize) -> Option<Process> {
+    let mut p = Process::new( stack_size )?;
+
+    p.name = String::from( name );
+    p.trap_frame.ELR = f as u64;
+    p.trap_frame.SP = p.stack.top().as_u64(); //runs on SP_EL0 pointing at its own stack
+    p.trap_frame.SPSR = 0b0100; //EL1t with DAIF cleared
+    p.trap_frame.x0 = arg;
+    p.trap_frame.x30 = exit as u64; //returning from `f` branches to `exit`
+
+    Some( p )
+}
+
+// Kernel threads run at EL1t and are switched out like any other process: the
+// calls below trap to the scheduler with `svc`. They must not be made from
+// exception handlers.
+
+/// Terminates the calling kernel thread.
+pub extern "C" fn exit() -> ! {
+    unsafe {
+        let _ = svc!( nr::EXIT );
+    }
+    unreachable!( "exit returned" );
+}
+
+/// Lets other processes run for `ms` milliseconds and returns the milliseconds
+/// that actually elapsed. Fails with `Interrupted` if a signal arrives first.
+pub fn sleep(ms: u32) -> Result<u32, OsError> {
+    unsafe { svc!( nr::SLEEP, ms ).map( |( elapsed, _ )| elapsed as u32 ) }
+}
+
+/// Reads from the file open under `fd` of the calling kernel thread into
+/// `buf`, letting other processes run until data is available. Returns the
+/// number of bytes read.
+pub fn read(fd: u64, buf: &mut [u8]) -> Result<usize, OsError> {
+    unsafe { svc!( nr::READ, fd, buf.as_mut_ptr(), buf.len() ).map( |( n, _ )| n as usize ) }
+}
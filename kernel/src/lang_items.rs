@@ -5,12 +5,15 @@ pub extern fn panic_fmt(fmt: ::std::fmt::Arguments, file: &'static str, line: u3
     // FIXME: Print `fmt`, `file`, and `line` to the console.
 
     use console::kprint;
+    use backtrace;
     
     kprint!("Ops, something went wrong... {{\n");
     kprint!("    Info: {}\n", fmt );
     kprint!("    Source: file: {}, line: {}, col: {}\n", file, line, col );
     kprint!("}}\n");
 
+    backtrace::print_here();
+
     loop { unsafe { asm!("wfe") } }
 }
 
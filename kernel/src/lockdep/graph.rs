@@ -0,0 +1,200 @@
+use std::fmt;
+
+/// Maximum number of distinct locks tracked.
+pub const MAX_CLASSES: usize = 64;
+
+/// Maximum number of dependencies whose acquisition sites are recorded.
+/// Dependencies observed once the table is full are not tracked.
+pub const MAX_EDGES: usize = 256;
+
+/// Marks a class without a parent while searching for a path.
+const NONE: u8 = !0;
+
+/// A lock held by a core and where it was acquired.
+#[derive(Copy, Clone, Debug)]
+pub struct Held {
+    pub class: usize,
+    pub site: usize,
+}
+
+/// Acquisition sites of the first time a dependency was observed: the site
+/// of the lock already held and the site of the lock acquired.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Edge {
+    pub held_site: usize,
+    pub site: usize,
+}
+
+/// A dependency cycle: the new edge `from -> to` closes the path of classes
+/// `path[..len]` leading from `to` back to `from`.
+#[derive(Debug)]
+struct Cycle {
+    from: u8,
+    to: u8,
+    edge: Edge,
+    path: [u8; MAX_CLASSES],
+    len: usize,
+}
+
+/// The dependency graph between lock classes, kept in place so that a
+/// search never needs more than a few bytes of the caller's stack.
+#[derive(Debug)]
+pub struct Graph {
+    classes: [usize; MAX_CLASSES],
+    nclasses: usize,
+    /// Bit `m` of `succ[n]` is set if class `m` was acquired while holding
+    /// class `n`.
+    succ: [u64; MAX_CLASSES],
+    /// The sites of each dependency, in the order they were observed.
+    edges: [(u8, u8, Edge); MAX_EDGES],
+    nedges: usize,
+    /// The first cycle found. The graph is frozen from then on.
+    cycle: Option<Cycle>,
+}
+
+impl Graph {
+    /// Returns a graph without any lock.
+    pub const fn new() -> Graph {
+        Graph {
+            classes: [0; MAX_CLASSES],
+            nclasses: 0,
+            succ: [0; MAX_CLASSES],
+            edges: [(0, 0, Edge { held_site: 0, site: 0 }); MAX_EDGES],
+            nedges: 0,
+            cycle: None,
+        }
+    }
+
+    /// Returns `true` once a cycle has been found.
+    pub fn disabled(&self) -> bool {
+        self.cycle.is_some()
+    }
+
+    /// Returns the class of `lock`, if it is known.
+    pub fn find(&self, lock: usize) -> Option<usize> {
+        self.classes[..self.nclasses].iter().position(|x| *x == lock)
+    }
+
+    /// Returns the class of `lock`, registering it if needed. Returns `None`
+    /// if every class is in use or the graph is frozen.
+    pub fn class(&mut self, lock: usize) -> Option<usize> {
+        if let Some(i) = self.find(lock) {
+            return Some(i)
+        }
+        if self.nclasses == MAX_CLASSES || self.disabled() {
+            return None
+        }
+        self.classes[self.nclasses] = lock;
+        self.nclasses += 1;
+        Some(self.nclasses - 1)
+    }
+
+    /// Returns the sites of the dependency `from -> to`, if it was recorded.
+    fn edge(&self, from: usize, to: usize) -> Option<Edge> {
+        self.edges[..self.nedges].iter()
+            .find(|x| x.0 as usize == from && x.1 as usize == to)
+            .map(|x| x.2)
+    }
+
+    /// Searches for a path of dependencies from `from` to `to`, breadth
+    /// first. On success, the classes of the path after `from` are stored in
+    /// `path` and their number is returned.
+    fn path(&self, from: usize, to: usize, path: &mut [u8; MAX_CLASSES]) -> Option<usize> {
+        let mut parent = [NONE; MAX_CLASSES];
+        let mut queue = [0u8; MAX_CLASSES];
+        let (mut head, mut tail) = (0, 1);
+        queue[0] = from as u8;
+        parent[from] = from as u8;
+
+        while head < tail {
+            let n = queue[head] as usize;
+            head += 1;
+            if n == to {
+                //walk back to `from`, then reverse
+                let mut len = 0;
+                let mut c = to;
+                while c != from {
+                    path[len] = c as u8;
+                    len += 1;
+                    c = parent[c] as usize;
+                }
+                path[..len].reverse();
+                return Some(len)
+            }
+            for m in 0..self.nclasses {
+                if self.succ[n] & (1 << m) != 0 && parent[m] == NONE {
+                    parent[m] = n as u8;
+                    queue[tail] = m as u8;
+                    tail += 1;
+                }
+            }
+        }
+        None
+    }
+
+    /// Records the dependencies of acquiring `lock` at `site` while holding
+    /// the locks `held`. Returns `true` if this closes a cycle, which is kept
+    /// for `report` and freezes the graph.
+    pub fn depend(&mut self, held: &[Held], lock: usize, site: usize) -> bool {
+        let to = match self.class(lock) {
+            Some(x) => x,
+            None => return false,
+        };
+
+        for h in held {
+            if h.class == to || self.succ[h.class] & (1 << to) != 0 {
+                continue
+            }
+
+            let edge = Edge { held_site: h.site, site };
+            let mut path = [0; MAX_CLASSES];
+            if let Some(len) = self.path(to, h.class, &mut path) {
+                self.cycle = Some(Cycle { from: h.class as u8, to: to as u8, edge, path, len });
+                return true
+            }
+            if self.nedges == MAX_EDGES {
+                continue
+            }
+            self.succ[h.class] |= 1 << to;
+            self.edges[self.nedges] = (h.class as u8, to as u8, edge);
+            self.nedges += 1;
+        }
+        false
+    }
+
+    /// Returns the report of the cycle found, if any.
+    pub fn report(&self) -> Option<Report> {
+        self.cycle.as_ref().map(|cycle| Report { graph: self, cycle })
+    }
+}
+
+/// The report of a dependency cycle, with the acquisition sites of every
+/// edge.
+pub struct Report<'a> {
+    graph: &'a Graph,
+    cycle: &'a Cycle,
+}
+
+impl<'a> fmt::Display for Report<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let classes = &self.graph.classes;
+        let cycle = self.cycle;
+        let (from, to) = (cycle.from as usize, cycle.to as usize);
+
+        writeln!(f, "lockdep: possible circular locking dependency detected")?;
+        writeln!(f, "    acquiring lock {:#x} at {:#x}", classes[to], cycle.edge.site)?;
+        writeln!(f, "    while holding lock {:#x} acquired at {:#x}", classes[from], cycle.edge.held_site)?;
+        writeln!(f, "    existing dependency chain:")?;
+
+        let mut prev = to;
+        for &next in cycle.path[..cycle.len].iter() {
+            let next = next as usize;
+            //every edge of the path was recorded before the graph froze
+            let edge = self.graph.edge(prev, next).unwrap();
+            writeln!(f, "        lock {:#x} acquired at {:#x} while holding lock {:#x} acquired at {:#x}",
+                     classes[next], edge.site, classes[prev], edge.held_site)?;
+            prev = next;
+        }
+        writeln!(f, "lockdep: checking disabled")
+    }
+}
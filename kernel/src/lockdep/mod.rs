@@ -0,0 +1,169 @@
+//! Lock dependency checker.
+//!
+//! When the kernel is built with the `lockdep` feature, every acquisition of a
+//! `Mutex` with static storage (`ALLOCATOR`, `FILE_SYSTEM`, `SCHEDULER`,
+//! `CONSOLE`, ...) records a dependency edge from each lock already held by
+//! the core to the lock being acquired, together with the code addresses
+//! where both were taken. Before an edge is added, the graph is searched for a
+//! path in the opposite direction. If one exists, the two locks can be taken
+//! in conflicting orders and the cycle is reported with the acquisition sites
+//! of every edge, even if the deadlock has not happened yet. Checking stops
+//! after the first report.
+//!
+//! Without the feature, all hooks compile to nothing.
+
+pub use self::imp::{will_acquire, acquired, released};
+
+#[cfg(any(feature = "lockdep", test))]
+mod graph;
+#[cfg(test)]
+mod tests;
+
+#[cfg(not(feature = "lockdep"))]
+mod imp {
+    /// Called before spinning on the lock at `lock` from `site`.
+    #[inline(always)]
+    pub fn will_acquire(_lock: usize, _site: usize) { }
+
+    /// Called once the lock at `lock` is held, acquired from `site`.
+    #[inline(always)]
+    pub fn acquired(_lock: usize, _site: usize) { }
+
+    /// Called once the lock at `lock` is released.
+    #[inline(always)]
+    pub fn released(_lock: usize) { }
+}
+
+#[cfg(feature = "lockdep")]
+mod imp {
+    use std::cell::UnsafeCell;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use aarch64;
+    use console::kprint;
+    use smp::{self, NCORES};
+    use super::graph::{Graph, Held};
+
+    /// Maximum number of locks held at once by a core.
+    const MAX_HELD: usize = 16;
+
+    struct State {
+        graph: Graph,
+        held: [[Held; MAX_HELD]; NCORES],
+        depth: [usize; NCORES],
+        /// Set while a core is printing a report, whose console locking must
+        /// not be checked.
+        reporting: [bool; NCORES],
+    }
+
+    /// The checker state, only accessed with `STATE_LOCK` held.
+    struct Shared(UnsafeCell<State>);
+
+    unsafe impl Sync for Shared {}
+
+    static STATE: Shared = Shared(UnsafeCell::new(State {
+        graph: Graph::new(),
+        held: [[Held { class: 0, site: 0 }; MAX_HELD]; NCORES],
+        depth: [0; NCORES],
+        reporting: [false; NCORES],
+    }));
+
+    /// Protects `STATE`. Taken with IRQs masked.
+    static STATE_LOCK: AtomicBool = AtomicBool::new(false);
+
+    extern "C" {
+        static _start: u8;
+        static _end: u8;
+    }
+
+    /// Returns `true` for locks with static storage. Locks embedded in heap
+    /// objects are not tracked since their addresses get reused.
+    fn tracked(lock: usize) -> bool {
+        unsafe {
+            lock >= &_start as *const u8 as usize && lock < &_end as *const u8 as usize
+        }
+    }
+
+    /// Runs `f` on the checker state with IRQs masked.
+    fn lock_state<R, F: FnOnce(&mut State, usize) -> R>(f: F) -> R {
+        let daif = aarch64::irq_save();
+        let core = smp::core();
+
+        while STATE_LOCK.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {}
+        let r = f(unsafe { &mut *STATE.0.get() }, core);
+        STATE_LOCK.store(false, Ordering::Release);
+
+        aarch64::irq_restore(daif);
+        r
+    }
+
+    /// Like `lock_state`, but returns `None` without running `f` if the
+    /// calling core is printing a report.
+    fn with_state<R, F: FnOnce(&mut State, usize) -> R>(f: F) -> Option<R> {
+        lock_state(|s, core| if s.reporting[core] { None } else { Some(f(s, core)) })
+    }
+
+    /// Called before spinning on the lock at `lock` from `site`. Reports a
+    /// cycle if acquiring it while holding the core's current locks may
+    /// deadlock.
+    pub fn will_acquire(lock: usize, site: usize) {
+        if !tracked(lock) {
+            return
+        }
+
+        let found = with_state(|s, core| {
+            if s.graph.disabled() {
+                return false
+            }
+            let found = s.graph.depend(&s.held[core][..s.depth[core]], lock, site);
+            s.reporting[core] = found;
+            found
+        });
+
+        if found == Some(true) {
+            //the graph is frozen once a cycle is found, so it is read without
+            //`STATE_LOCK`: another core may spin on it with the console locked
+            let graph = unsafe { &(*STATE.0.get()).graph };
+            if let Some(report) = graph.report() {
+                kprint!("{}", report);
+            }
+            lock_state(|s, core| s.reporting[core] = false);
+        }
+    }
+
+    /// Called once the lock at `lock` is held, acquired from `site`.
+    pub fn acquired(lock: usize, site: usize) {
+        if !tracked(lock) {
+            return
+        }
+
+        with_state(|s, core| {
+            if let Some(class) = s.graph.class(lock) {
+                if s.depth[core] < MAX_HELD {
+                    s.held[core][s.depth[core]] = Held { class, site };
+                    s.depth[core] += 1;
+                }
+            }
+        });
+    }
+
+    /// Called once the lock at `lock` is released.
+    pub fn released(lock: usize) {
+        if !tracked(lock) {
+            return
+        }
+
+        with_state(|s, core| {
+            let depth = s.depth[core];
+            let held = &mut s.held[core];
+            if let Some(class) = s.graph.find(lock) {
+                if let Some(i) = held[..depth].iter().rposition(|h| h.class == class) {
+                    for j in i..depth - 1 {
+                        held[j] = held[j + 1];
+                    }
+                    s.depth[core] -= 1;
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,104 @@
+use lockdep::graph::{Graph, Held, MAX_CLASSES};
+
+const A: usize = 0x1000;
+const B: usize = 0x2000;
+const C: usize = 0x3000;
+
+/// Acquires `lock` at `site` while holding `held`, given as lock and site
+/// pairs. Returns `true` if a cycle was found.
+fn acquire(g: &mut Graph, held: &[(usize, usize)], lock: usize, site: usize) -> bool {
+    let held: Vec<Held> = held.iter()
+        .map(|&(lock, site)| Held { class: g.class(lock).unwrap(), site })
+        .collect();
+    g.depend(&held, lock, site)
+}
+
+#[test]
+fn consistent_order_is_not_reported() {
+    let mut g = Graph::new();
+    assert!(!acquire(&mut g, &[(A, 0x10)], B, 0x14));
+    assert!(!acquire(&mut g, &[(A, 0x10)], B, 0x14));
+    assert!(!acquire(&mut g, &[(A, 0x10), (B, 0x14)], C, 0x18));
+    assert!(!acquire(&mut g, &[(A, 0x20)], C, 0x24));
+    assert!(!g.disabled());
+    assert!(g.report().is_none());
+}
+
+#[test]
+fn recursive_acquisition_is_not_reported() {
+    let mut g = Graph::new();
+    assert!(!acquire(&mut g, &[(A, 0x10)], A, 0x14));
+    assert!(!g.disabled());
+}
+
+#[test]
+fn inverted_pair_is_reported() {
+    let mut g = Graph::new();
+    assert!(!acquire(&mut g, &[(A, 0x10)], B, 0x14));
+    assert!(acquire(&mut g, &[(B, 0x20)], A, 0x24));
+    assert!(g.disabled());
+
+    let report = format!("{}", g.report().unwrap());
+    assert_eq!(report, "\
+lockdep: possible circular locking dependency detected
+    acquiring lock 0x1000 at 0x24
+    while holding lock 0x2000 acquired at 0x20
+    existing dependency chain:
+        lock 0x2000 acquired at 0x14 while holding lock 0x1000 acquired at 0x10
+lockdep: checking disabled
+");
+}
+
+#[test]
+fn transitive_cycle_reports_every_edge() {
+    let mut g = Graph::new();
+    assert!(!acquire(&mut g, &[(A, 0x10)], B, 0x14));
+    assert!(!acquire(&mut g, &[(B, 0x20)], C, 0x24));
+    assert!(acquire(&mut g, &[(C, 0x30)], A, 0x34));
+
+    let report = format!("{}", g.report().unwrap());
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines[1], "    acquiring lock 0x1000 at 0x34");
+    assert_eq!(lines[2], "    while holding lock 0x3000 acquired at 0x30");
+    assert_eq!(&lines[4..6], &[
+        "        lock 0x2000 acquired at 0x14 while holding lock 0x1000 acquired at 0x10",
+        "        lock 0x3000 acquired at 0x24 while holding lock 0x2000 acquired at 0x20",
+    ]);
+    assert_eq!(lines.len(), 7);
+}
+
+#[test]
+fn shortest_path_is_reported() {
+    let mut g = Graph::new();
+    assert!(!acquire(&mut g, &[(A, 0x10)], B, 0x14));
+    assert!(!acquire(&mut g, &[(B, 0x20)], C, 0x24));
+    assert!(!acquire(&mut g, &[(A, 0x30)], C, 0x34));
+    assert!(acquire(&mut g, &[(C, 0x40)], A, 0x44));
+
+    let report = format!("{}", g.report().unwrap());
+    assert_eq!(report.lines().filter(|l| l.contains("while holding lock 0x1000")).count(), 1);
+    assert!(report.contains("lock 0x3000 acquired at 0x34 while holding lock 0x1000 acquired at 0x30"));
+}
+
+#[test]
+fn graph_freezes_after_first_cycle() {
+    let mut g = Graph::new();
+    assert!(!acquire(&mut g, &[(A, 0x10)], B, 0x14));
+    assert!(acquire(&mut g, &[(B, 0x20)], A, 0x24));
+    let first = format!("{}", g.report().unwrap());
+
+    assert!(g.class(C).is_none());
+    assert!(!g.depend(&[], C, 0x30));
+    assert_eq!(format!("{}", g.report().unwrap()), first);
+}
+
+#[test]
+fn classes_are_bounded() {
+    let mut g = Graph::new();
+    for i in 0..MAX_CLASSES {
+        assert_eq!(g.class(0x1000 + i * 8), Some(i));
+    }
+    assert_eq!(g.class(0x1000), Some(0));
+    assert!(g.class(0x1000 + MAX_CLASSES * 8).is_none());
+    assert!(!acquire(&mut g, &[(0x1000, 0x10)], 0x1000 + MAX_CLASSES * 8, 0x14));
+}
@@ -1,14 +1,32 @@
 use std::sync::atomic::{AtomicBool, AtomicUsize};
-use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::Ordering::{Relaxed, Acquire, Release};
 use std::cell::UnsafeCell;
 use std::ops::{DerefMut, Deref, Drop};
 use std::fmt;
 
+use aarch64;
+use lockdep;
+
+/// Value of `owner` while the lock is free.
+const NO_OWNER: usize = usize::max_value();
+
+/// A spinlock shared between cores.
+///
+/// The lock is recursive per core: a core that already holds the lock can
+/// acquire it again, and the lock is released once every guard is dropped.
+/// Because ownership is tracked by core and not by process, a `Mutex` must not
+/// be held across a point where the core can switch to other code that takes
+/// the same lock, such as an IRQ handler or another process after a timer
+/// preemption. Use `IrqMutex` for those locks.
+///
+/// Exclusive loads and stores need the MMU and data cache to be enabled on the
+/// Cortex-A53 to be observed by other cores.
 #[repr(align(32))]
 pub struct Mutex<T> {
     data: UnsafeCell<T>,
     lock: AtomicBool,
-    owner: AtomicUsize
+    owner: AtomicUsize,
+    depth: AtomicUsize,
 }
 
 unsafe impl<T: Send> Send for Mutex<T> { }
@@ -21,45 +39,99 @@ pub struct MutexGuard<'a, T: 'a> {
 impl<'a, T> !Send for MutexGuard<'a, T> { }
 unsafe impl<'a, T: Sync> Sync for MutexGuard<'a, T> { }
 
+/// Returns the identifier of the executing core.
+#[cfg(not(test))]
+fn this_core() -> usize {
+    unsafe { aarch64::affinity() }
+}
+
+#[cfg(test)]
+fn this_core() -> usize {
+    0
+}
+
 impl<T> Mutex<T> {
     pub const fn new(val: T) -> Mutex<T> {
         Mutex {
             lock: AtomicBool::new(false),
-            owner: AtomicUsize::new(usize::max_value()),
+            owner: AtomicUsize::new(NO_OWNER),
+            depth: AtomicUsize::new(0),
             data: UnsafeCell::new(val)
         }
     }
 }
 
 impl<T> Mutex<T> {
-    // Once MMU/cache is enabled, do the right thing here. For now, we don't
-    // need any real synchronization.
+    /// Attempts to acquire the lock without spinning. Succeeds if the lock is
+    /// free or already held by the calling core.
+    #[inline(never)]
     pub fn try_lock(&self) -> Option<MutexGuard<T>> {
-        let this = 0;
-        if !self.lock.load(Relaxed) || self.owner.load(Relaxed) == this {
-            self.lock.store(true, Relaxed);
-            self.owner.store(this, Relaxed);
-            Some(MutexGuard { lock: &self })
-        } else {
-            None
-        }
+        let site = aarch64::lr();
+        self.try_lock_at(site)
     }
 
-    // Once MMU/cache is enabled, do the right thing here. For now, we don't
-    // need any real synchronization.
+    /// Spins until the lock is acquired.
     #[inline(never)]
     pub fn lock(&self) -> MutexGuard<T> {
-        // Wait until we can "aquire" the lock, then "acquire" it.
+        let site = aarch64::lr();
+        self.lock_at(site)
+    }
+
+    /// Returns `true` if the lock is held by the calling core.
+    fn held_here(&self) -> bool {
+        self.owner.load(Relaxed) == this_core()
+    }
+
+    /// Address identifying this lock to the lock dependency checker.
+    fn addr(&self) -> usize {
+        self as *const Mutex<T> as usize
+    }
+
+    /// `lock()` on behalf of the caller at `site`.
+    fn lock_at(&self, site: usize) -> MutexGuard<T> {
+        if !self.held_here() {
+            lockdep::will_acquire(self.addr(), site);
+        }
+
         loop {
-            match self.try_lock() {
+            match self.try_lock_at(site) {
                 Some(guard) => return guard,
-                None => continue
+                None => {
+                    //wait for the lock to look free before retrying the
+                    //exclusive access
+                    while self.lock.load(Relaxed) {}
+                }
             }
         }
     }
 
+    /// `try_lock()` on behalf of the caller at `site`.
+    fn try_lock_at(&self, site: usize) -> Option<MutexGuard<T>> {
+        let this = this_core();
+
+        //only this core ever stores its own id in `owner`, so a match means
+        //the lock is held by this core
+        if self.owner.load(Relaxed) == this {
+            self.depth.fetch_add(1, Relaxed);
+            return Some(MutexGuard { lock: &self })
+        }
+
+        if self.lock.compare_exchange(false, true, Acquire, Relaxed).is_ok() {
+            self.owner.store(this, Relaxed);
+            self.depth.store(1, Relaxed);
+            lockdep::acquired(self.addr(), site);
+            Some(MutexGuard { lock: &self })
+        } else {
+            None
+        }
+    }
+
     fn unlock(&self) {
-        self.lock.store(false, Relaxed);
+        if self.depth.fetch_sub(1, Relaxed) == 1 {
+            lockdep::released(self.addr());
+            self.owner.store(NO_OWNER, Relaxed);
+            self.lock.store(false, Release);
+        }
     }
 }
 
@@ -91,3 +163,91 @@ impl<T: fmt::Debug> fmt::Debug for Mutex<T> {
         }
     }
 }
+
+/// A `Mutex` that masks IRQs on the holding core while the lock is held.
+///
+/// An IRQ handler can never interrupt the holder, so a lock shared with IRQ
+/// handlers or with preemptible processes cannot deadlock or be re-entered
+/// on the same core. The previous `DAIF.I` state is restored when the guard is
+/// dropped.
+pub struct IrqMutex<T>(Mutex<T>);
+
+pub struct IrqMutexGuard<'a, T: 'a> {
+    guard: Option<MutexGuard<'a, T>>,
+    daif: u64,
+}
+
+impl<'a, T> !Send for IrqMutexGuard<'a, T> { }
+
+impl<T> IrqMutex<T> {
+    pub const fn new(val: T) -> IrqMutex<T> {
+        IrqMutex(Mutex::new(val))
+    }
+
+    /// Masks IRQs and attempts to acquire the lock without spinning. IRQs are
+    /// restored if the lock could not be acquired.
+    #[inline(never)]
+    pub fn try_lock(&self) -> Option<IrqMutexGuard<T>> {
+        let site = aarch64::lr();
+        self.try_lock_at(site)
+    }
+
+    /// Spins until the lock is acquired. IRQs stay enabled while spinning.
+    #[inline(never)]
+    pub fn lock(&self) -> IrqMutexGuard<T> {
+        let site = aarch64::lr();
+
+        if !self.0.held_here() {
+            lockdep::will_acquire(self.0.addr(), site);
+        }
+
+        loop {
+            match self.try_lock_at(site) {
+                Some(guard) => return guard,
+                None => continue
+            }
+        }
+    }
+
+    fn try_lock_at(&self, site: usize) -> Option<IrqMutexGuard<T>> {
+        let daif = aarch64::irq_save();
+        match self.0.try_lock_at(site) {
+            Some(guard) => Some(IrqMutexGuard { guard: Some(guard), daif }),
+            None => {
+                aarch64::irq_restore(daif);
+                None
+            }
+        }
+    }
+}
+
+impl<'a, T: 'a> Deref for IrqMutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl<'a, T: 'a> DerefMut for IrqMutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.guard.as_mut().unwrap()
+    }
+}
+
+impl<'a, T: 'a> Drop for IrqMutexGuard<'a, T> {
+    fn drop(&mut self) {
+        //release the lock before IRQs can come in again
+        self.guard.take();
+        aarch64::irq_restore(self.daif);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IrqMutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.try_lock() {
+            Some(guard) => f.debug_struct("IrqMutex").field("data", &&*guard).finish(),
+            None => f.debug_struct("IrqMutex").field("data", &"<locked>").finish()
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use fs::{FileSystem, traits};
+use ipc::Pipe;
+use mutex::IrqMutex;
+
+/// A file of the file system opened by a process.
+pub type File = <&'static FileSystem as traits::FileSystem>::File;
+
+/// An open file of a process.
+#[derive(Debug)]
+pub enum Descriptor {
+    /// The read end of a pipe.
+    PipeRead(Arc<Pipe>),
+    /// The write end of a pipe.
+    PipeWrite(Arc<Pipe>),
+    /// A file opened for reading, with its position. The lock masks IRQs so a
+    /// process sharing the file can't preempt the holder and re-enter it.
+    File(Arc<IrqMutex<File>>),
+    /// The console, read from the UART.
+    Console,
+}
+
+impl Drop for Descriptor {
+    fn drop(&mut self) {
+        match *self {
+            Descriptor::PipeRead(ref p) => p.close_read(),
+            Descriptor::PipeWrite(ref p) => p.close_write(),
+            Descriptor::File(_) | Descriptor::Console => {},
+        }
+    }
+}
+
+/// Type alias for the index of an open file in a process's file table.
+pub type Fd = usize;
+
+/// The open files of a process, indexed by file descriptor. Dropping the
+/// table closes every file left open.
+#[derive(Debug)]
+pub struct Files(Vec<Option<Descriptor>>);
+
+impl Files {
+    /// Returns an empty file table.
+    pub fn new() -> Files {
+        Files(Vec::new())
+    }
+
+    /// Stores `d` under the lowest free file descriptor and returns it.
+    pub fn insert(&mut self, d: Descriptor) -> Fd {
+        match self.0.iter().position( |x| x.is_none() ) {
+            Some(fd) => {
+                self.0[fd] = Some( d );
+                fd
+            },
+            None => {
+                self.0.push( Some( d ) );
+                self.0.len() - 1
+            },
+        }
+    }
+
+    /// Returns the file open under `fd`.
+    pub fn get(&self, fd: Fd) -> Option<&Descriptor> {
+        self.0.get( fd )?.as_ref()
+    }
+
+    /// Removes and returns the file open under `fd`. The file is closed once
+    /// the returned value is dropped.
+    pub fn remove(&mut self, fd: Fd) -> Option<Descriptor> {
+        self.0.get_mut( fd )?.take()
+    }
+
+    /// Returns the number of open files.
+    pub fn len(&self) -> usize {
+        self.0.iter().filter( |x| x.is_some() ).count()
+    }
+}
@@ -0,0 +1,70 @@
+use abi::resource;
+
+pub use abi::resource::UNLIMITED;
+
+/// A resource whose use by a process is limited.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Resource {
+    /// Bytes of kernel heap held by the process: its stack, the shared memory
+    /// regions it maps and its `mmap` memory. Checked when memory is
+    /// allocated or mapped.
+    Memory = 0,
+    /// Number of open files. Checked when files are opened.
+    Files = 1,
+    /// Number of child processes that exist at once. Checked when a process
+    /// is created.
+    Children = 2,
+    /// Milliseconds of CPU time. A process that uses more is terminated.
+    CpuTime = 3,
+}
+
+/// Number of limited resources.
+pub const NRESOURCES: usize = 4;
+
+impl Resource {
+    /// Returns the resource numbered `n`, as passed to `getrlimit`.
+    pub fn from_u64(n: u64) -> Option<Resource> {
+        match n {
+            resource::MEMORY => Some(Resource::Memory),
+            resource::FILES => Some(Resource::Files),
+            resource::CHILDREN => Some(Resource::Children),
+            resource::CPU_TIME => Some(Resource::CpuTime),
+            _ => None,
+        }
+    }
+}
+
+/// The resource limits of a process. A new process inherits the limits of
+/// its parent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits([u64; NRESOURCES]);
+
+impl Limits {
+    /// Returns the limits of a process started by the kernel: 16MiB of
+    /// memory, 32 open files, 16 children and unlimited CPU time.
+    pub fn new() -> Limits {
+        Limits([16 << 20, 32, 16, UNLIMITED])
+    }
+
+    /// Returns the limit of `r`.
+    pub fn get(&self, r: Resource) -> u64 {
+        self.0[r as usize]
+    }
+
+    /// Sets the limit of `r` to `limit` and returns the previous limit.
+    /// Limits can only be lowered: returns `None` and leaves the limit
+    /// unchanged if `limit` is higher than the current one.
+    pub fn set(&mut self, r: Resource, limit: u64) -> Option<u64> {
+        let old = self.0[r as usize];
+        if limit > old {
+            return None
+        }
+        self.0[r as usize] = limit;
+        Some( old )
+    }
+
+    /// Returns `true` if `used` units of `r` are within the limit.
+    pub fn allows(&self, r: Resource, used: u64) -> bool {
+        used <= self.0[r as usize]
+    }
+}
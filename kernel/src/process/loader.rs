@@ -0,0 +1,47 @@
+use std::ptr;
+use std::sync::Arc;
+
+use aarch64;
+use abi::{image, OsError};
+use ipc::Region;
+use process::{Process, Stack, Descriptor, Resource};
+
+/// Creates, but does not schedule, a process running the user program binary
+/// `image` at EL0, named `name` and passed `args`. See `abi::image` for the
+/// format of the binary.
+///
+/// The binary is copied to the start of a zeroed region mapped by the process,
+/// large enough for its `.bss`. The process is entered at the first byte of
+/// the region on its own stack of the default size, with the console open as
+/// standard input, output and error, file descriptors `0` to `2`.
+///
+/// Fails with `InvalidArgument` if `image` is not a program binary and with
+/// `NoMemory` if the process could not be allocated or would be over its
+/// memory limit.
+pub fn load(image: &[u8], name: &str, args: &[&str]) -> Result<Process, OsError> {
+    let size = image::mem_size( image ).ok_or( OsError::InvalidArgument )?;
+
+    let mut p = Process::new( Stack::SIZE ).ok_or( OsError::NoMemory )?;
+    if !p.can_use( Resource::Memory, size as u64 ) {
+        return Err( OsError::NoMemory )
+    }
+    let region = Region::anonymous( size ).ok_or( OsError::NoMemory )?;
+
+    let entry = region.start().as_u64();
+    unsafe {
+        ptr::copy_nonoverlapping( image.as_ptr(), entry as *mut u8, image.len() );
+    }
+    aarch64::sync_icache(); //no stale instructions at the new code
+
+    p.name = String::from( name );
+    p.args = args.iter().map( |x| String::from( *x ) ).collect();
+    p.regions.push( Arc::new( region ) );
+    for _ in 0..3 {
+        p.files.insert( Descriptor::Console ); //standard input, output and error
+    }
+    p.trap_frame.ELR = entry;
+    p.trap_frame.SP = p.stack.top().as_u64();
+    p.trap_frame.SPSR = 0; //EL0t with DAIF cleared
+
+    Ok( p )
+}
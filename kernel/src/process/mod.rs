@@ -2,8 +2,25 @@ mod process;
 mod state;
 mod scheduler;
 mod stack;
+mod wait_queue;
+mod files;
+pub mod signal;
+mod table;
+mod limits;
+mod user;
+mod loader;
+
+#[cfg(test)]
+mod tests;
 
 pub use self::process::{Process, Id};
 pub use self::state::State;
-pub use self::scheduler::{GlobalScheduler, TICK};
+pub use self::scheduler::{GlobalScheduler, ProcessInfo, AffinityError, TICK, is_traced};
 pub use self::stack::Stack;
+pub use self::wait_queue::WaitQueue;
+pub use self::files::{Files, Descriptor, Fd, File};
+pub use self::signal::{Signals, Signal};
+pub use self::table::{ProcessTable, MAX_PROCESSES};
+pub use self::limits::{Limits, Resource, UNLIMITED};
+pub use self::user::{UserPtr, UserSlice, Fault, Access};
+pub use self::loader::load;
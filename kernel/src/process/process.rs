@@ -1,5 +1,9 @@
+use std::sync::Arc;
+
+use ipc::{Mailbox, Region};
+use smp;
 use traps::TrapFrame;
-use process::{State, Stack};
+use process::{State, Stack, Files, Signals, Limits, Resource};
 
 /// Type alias for the type of a process ID.
 pub type Id = u64;
@@ -13,16 +17,53 @@ pub struct Process {
     pub stack: Stack,
     /// The scheduling state of the process.
     pub state: State,
+    /// A human readable name used in diagnostics, and the first argument of
+    /// the process.
+    pub name: String,
+    /// The arguments of the process after its name.
+    pub args: Vec<String>,
+    /// The files the process has open.
+    pub files: Files,
+    /// Messages sent to the process and not yet received.
+    pub mailbox: Mailbox,
+    /// The shared memory regions mapped by the process.
+    pub regions: Vec<Arc<Region>>,
+    /// Pending and blocked signals and signal actions.
+    pub signals: Signals,
+    /// ID of the process that created this one, if it still exists.
+    pub parent: Option<Id>,
+    /// IDs of the processes this one created that still exist.
+    pub children: Vec<Id>,
+    /// Limits on the resources the process may use.
+    pub limits: Limits,
+    /// Microseconds the process spent running.
+    pub cpu_time: u64,
+    /// Time in microseconds at which the process was last switched in.
+    pub scheduled_at: u64,
+    /// Bit mask of the cores the process may run on, bit `n` for the core
+    /// whose `aarch64::affinity()` is `n`.
+    pub affinity: usize,
+    /// Whether the system calls of the process are logged to the console.
+    pub traced: bool,
+    /// The trace line of a system call completed while the process waited,
+    /// logged when the process is switched in.
+    pub trace: Option<String>,
+    /// The token of the last wait the process blocked in.
+    pub wait: u64,
 }
 
 impl Process {
     /// Creates a new process with a zeroed `TrapFrame` (the default), a zeroed
-    /// stack of the default size, and a state of `Ready`.
+    /// stack of at least `stack_size` bytes, a state of `Ready`, an empty
+    /// name, no arguments, no open files, an empty mailbox, no shared memory,
+    /// default signal actions, no parent or children, the default resource
+    /// limits, no CPU time used, an affinity to every core and tracing
+    /// disabled. The stack size is rounded up to a multiple of `Stack::ALIGN`.
     ///
     /// If enough memory could not be allocated to start the process, returns
     /// `None`. Otherwise returns `Some` of the new `Process`.
-    pub fn new() -> Option<Process> {
-        let s = match Stack::new() {
+    pub fn new(stack_size: usize) -> Option<Process> {
+        let s = match Stack::new( stack_size ) {
             Some(x) => x,
             _ => { return None },
         };
@@ -30,9 +71,49 @@ impl Process {
             trap_frame: Box::new(TrapFrame::default()),
             stack: s,
             state: State::Ready,
+            name: String::new(),
+            args: Vec::new(),
+            files: Files::new(),
+            mailbox: Mailbox::new(),
+            regions: Vec::new(),
+            signals: Signals::new(),
+            parent: None,
+            children: Vec::new(),
+            limits: Limits::new(),
+            cpu_time: 0,
+            scheduled_at: 0,
+            affinity: smp::ALL_CORES,
+            traced: false,
+            trace: None,
+            wait: 0,
         } )
     }
 
+    /// Returns how much of `r` the process uses, in the unit of its limit.
+    pub fn usage(&self, r: Resource) -> u64 {
+        match r {
+            Resource::Memory => {
+                let regions: usize = self.regions.iter().map( |x| x.size() ).sum();
+                ( self.stack.size() + regions ) as u64
+            },
+            Resource::Files => self.files.len() as u64,
+            Resource::Children => self.children.len() as u64,
+            Resource::CpuTime => self.cpu_time / 1000,
+        }
+    }
+
+    /// Returns `true` if the process can use `amount` more of `r` without
+    /// going over its limit.
+    pub fn can_use(&self, r: Resource, amount: u64) -> bool {
+        self.limits.allows( r, self.usage( r ).saturating_add( amount ) )
+    }
+
+    /// Returns `true` if the process runs at EL1: a kernel thread, including
+    /// the idle processes.
+    pub fn is_kernel(&self) -> bool {
+        self.trap_frame.SPSR & 0b1100 != 0
+    }
+
     /// Returns `true` if this process is ready to be scheduled.
     ///
     /// This functions returns `true` only if one of the following holds:
@@ -1,21 +1,67 @@
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use mutex::Mutex;
-use process::{Process, State, Id};
+use abi::OsError;
+use mutex::IrqMutex;
+use smp;
+use sync::Waiter;
+use pi::timer;
+use process::{Process, State, Id, Stack, ProcessTable, Resource, Descriptor};
 use traps::TrapFrame;
 
 /// The `tick` time.
 
 pub const TICK: u32 = 1_000; //in microseconds
 
+/// Stack size of the idle kernel thread, which barely uses any.
+const IDLE_STACK_SIZE: usize = 1 << 12;
+
+/// Bit `n` is set while the process running on core `n` is traced. It is
+/// updated on every context switch, so that system calls check it without
+/// taking the scheduler lock.
+static TRACED: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns `true` if the process running on the calling core is traced. A
+/// change of `Process::traced` takes effect when the process is next switched
+/// in.
+pub fn is_traced() -> bool {
+    TRACED.load( Ordering::Relaxed ) & ( 1 << smp::core() ) != 0
+}
+
+/// A snapshot of a scheduled process used for diagnostics.
+#[derive(Debug)]
+pub struct ProcessInfo {
+    pub id: Id,
+    pub parent: Option<Id>,
+    pub name: String,
+    pub state: String,
+    /// The core whose run queue holds the process, `None` if blocked.
+    pub core: Option<usize>,
+    pub stack_used: usize,
+    pub stack_size: usize,
+    /// Milliseconds of CPU time used.
+    pub cpu_time: u64,
+}
+
+/// Why an affinity mask was refused.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AffinityError {
+    /// No process has the given ID.
+    NoSuchProcess,
+    /// The mask contains no core that is running the scheduler.
+    NoOnlineCore,
+    /// Idle processes are pinned to their core.
+    Idle,
+}
+
 /// Process scheduler for the entire machine.
 #[derive(Debug)]
-pub struct GlobalScheduler(Mutex<Option<Scheduler>>);
+pub struct GlobalScheduler(IrqMutex<Option<Scheduler>>);
 
 impl GlobalScheduler {
     /// Returns an uninitialized wrapper around a local scheduler.
     pub const fn uninitialized() -> GlobalScheduler {
-        GlobalScheduler(Mutex::new(None))
+        GlobalScheduler(IrqMutex::new(None))
     }
 
     /// Adds a process to the scheduler's queue and returns that process's ID.
@@ -28,43 +74,109 @@ impl GlobalScheduler {
     /// process to `new_state`, saving `tf` into the current process, and
     /// restoring the next process's trap frame into `tf`. For more details, see
     /// the documentation on `Scheduler::switch()`.
+    ///
+    /// A process switched out with `State::Dead` is dropped once the scheduler
+    /// lock is released, since closing its files may wake up other processes.
+    /// The trace lines of system calls completed by the scheduler are logged
+    /// then as well.
     #[must_use]
     pub fn switch(&self, new_state: State, tf: &mut TrapFrame) -> Option<Id> {
-        self.0.lock().as_mut().expect("scheduler uninitialized").switch(new_state, tf)
+        use std::mem;
+        use console::kprintln;
+
+        let mut guard = self.0.lock();
+        let scheduler = guard.as_mut().expect("scheduler uninitialized");
+        let id = scheduler.switch(new_state, tf);
+        let dead = mem::replace( &mut scheduler.dead, Vec::new() );
+        let traces = mem::replace( &mut scheduler.traces, Vec::new() );
+        drop( guard );
+        drop( dead );
+        for line in traces {
+            kprintln!( "{}", line );
+        }
+        id
+    }
+
+    /// Calls `f` on the process with ID `id`, ready or blocked. Returns `None`
+    /// if there is no such process. The lookup takes constant time.
+    pub fn with_process<R, F: FnOnce(&mut Process) -> R>(&self, id: Id, f: F) -> Option<R> {
+        self.0.lock().as_mut().expect("scheduler uninitialized").find_mut(id).map(f)
+    }
+
+    /// Blocks the current process and context switches `tf` to the next ready
+    /// process. The process stays out of the ready queue until it is woken up
+    /// with the returned waiter or interrupted by a signal.
+    pub fn block(&self, tf: &mut TrapFrame) -> Waiter {
+        let mut guard = self.0.lock();
+        let scheduler = guard.as_mut().expect("scheduler uninitialized");
+        let w = scheduler.wait( tf.TPIDR );
+        let _ = scheduler.switch( State::Blocked, tf );
+        w
+    }
+
+    /// Moves the process of `w` from the blocked set back into the ready queue.
+    /// Returns `false` if the process is no longer blocked in that wait.
+    pub fn wake(&self, w: Waiter) -> bool {
+        self.0.lock().as_mut().expect("scheduler uninitialized").wake(w)
+    }
+
+    /// Moves the process with ID `id` from the blocked set back into the ready
+    /// queue to deliver a signal. For more details, see the documentation on
+    /// `Scheduler::interrupt()`.
+    pub fn interrupt(&self, id: Id) -> bool {
+        self.0.lock().as_mut().expect("scheduler uninitialized").interrupt(id)
+    }
+
+    /// Sets the mask of the cores the process with ID `id` may run on and
+    /// returns the previous mask. For more details, see the documentation on
+    /// `Scheduler::set_affinity()`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `AffinityError::NoOnlineCore` if `mask` leaves out every
+    /// online core.
+    pub fn set_affinity(&self, id: Id, mask: usize) -> Result<usize, AffinityError> {
+        self.0.lock().as_mut().expect("scheduler uninitialized").set_affinity(id, mask)
+    }
+
+    /// Returns `true` if the process with ID `id` is the process with ID
+    /// `ancestor` or one of its descendants.
+    pub fn is_descendant(&self, id: Id, ancestor: Id) -> bool {
+        self.0.lock().as_ref().expect("scheduler uninitialized").is_descendant(id, ancestor)
+    }
+
+    /// Returns a snapshot of every process known to the scheduler, ready or
+    /// blocked, in ID order.
+    pub fn snapshot(&self) -> Vec<ProcessInfo> {
+        self.0.lock().as_ref().expect("scheduler uninitialized").snapshot()
     }
 
     /// Initializes the scheduler and starts executing processes in user space
-    /// using timer interrupt based preemptive scheduling. This method should
-    /// not return under normal conditions.
+    /// using timer interrupt based preemptive scheduling, releasing the
+    /// secondary cores along the way. This method should not return under
+    /// normal conditions.
     pub fn start(&self) {
         
         use func_shell;
         use func_periodic_print;
+        use kthread;
 
         //first process setup
         let mut trap_frame_ptr;
-        let p = match Process::new() {
+        let mut p = match Process::new( Stack::SIZE ) {
             Some( mut x) => {
                 //manually setup trap frame for the first process
                 x.trap_frame.ELR = func_shell as u64;
                 x.trap_frame.SP = x.stack.top().as_u64(); //SP to top of the process stack
-                x.trap_frame.SPSR = x.trap_frame.SPSR & !( ( ( 0b1111 as u64 ) << 6 ) ); //clear interrupt mask bits DAIF
+                x.trap_frame.SPSR = 0b0100; //EL1t with DAIF cleared, the shell shares kernel locks masking IRQs
+                x.files.insert( Descriptor::Console ); //standard input
                 trap_frame_ptr = x.trap_frame.clone();
                 x
             },
             _ => { panic!( "first process creation" ); },
         };
-
-        // //dummy process for testing context switch
-        let p2 = match Process::new() {
-            Some( mut x) => {
-                x.trap_frame.ELR = func_periodic_print as u64;
-                x.trap_frame.SP = x.stack.top().as_u64();
-                //x.trap_frame.SPSR = x.trap_frame.SPSR & !( ( ( 0b1111 as u64 ) << 6 ) );
-                x
-            },
-            _ => { panic!( "period print process creation" ); },
-        };
+        p.name = String::from( "shell" );
+        p.scheduled_at = timer::current_time();
 
         let mut s = Scheduler::new();
         *self.0.lock() = Some( s );
@@ -75,49 +187,117 @@ impl GlobalScheduler {
             _ => { panic!( "first process schedule add" ); },
         };
 
-        match self.add( p2 ) {
+        //dummy kernel thread for testing context switch
+        match kthread::spawn( func_periodic_print, 0, "periodic print" ) {
             Some( id ) => { kprintln!("period print process assigned id: {}", id ) },
-            _ => { panic!( "period print process schedule add" ); },
+            _ => { panic!( "period print process creation" ); },
+        };
+
+        //idle kernel thread, runs whenever every other process is waiting or blocked
+        let idle_process = kthread::process( idle, 0, "idle", IDLE_STACK_SIZE ).expect( "idle process creation" );
+        match self.0.lock().as_mut().unwrap().add_idle( 0, idle_process, false ) {
+            Some( id ) => { kprintln!("idle process assigned id: {}", id ) },
+            _ => { panic!( "idle process schedule add" ); },
         };
+
+        //cores 1 to 3 stay parked in the spin table: exclusive loads and stores
+        //need the MMU and D-cache enabled on the A53, which the kernel doesn't
+        //do yet, so `Mutex` can't be shared across cores. run queues other than
+        //core 0's stay empty and `smp::start_secondary_cores` is not called
         
-        //enable timer 1 interrupt
-        use pi::interrupt;
-        interrupt::Controller::new().enable( interrupt::Interrupt::Timer1 );
+        //preempt processes on timer 1 interrupts
+        use pi::interrupt::Interrupt;
+        use traps::irq;
+        irq::register( Interrupt::Timer1, irq::handle_timer1 );
         //set timer interrupt value
         use pi::timer;
         timer::tick_in( TICK );
         
         // skip continuing to HANDLER after context_restore because there isn't any other process
         // context to be restored from the stack
-        // x0 and x30 should also be reset since it is not in context_restore
-        unsafe {
-            asm!("mov SP, $0
-                  bl context_restore
-                  ldr x0, =_start
-                  mov SP, x0
-                  mov x0, #0
-                  mov x30, #0
-                  eret" //jump back to EL0 at func_shell
-                 :: "r"(trap_frame_ptr)
-                 :: "volatile");
-        }
+        unsafe { context_enter( &trap_frame_ptr, smp::stack_top( 0 ) ); } //jump to EL1t at func_shell
+    }
+
+    /// Starts scheduling on a secondary core. The core begins in its own idle
+    /// process and is preempted by its local generic timer, pulling work from
+    /// the other cores' run queues. Called once per core from
+    /// `kmain_secondary`.
+    pub fn start_core(&self) -> ! {
+
+        use kthread;
+        use pi::local_timer;
+
+        let core = smp::core();
+
+        let mut idle_process = kthread::process( idle, 0, "idle", IDLE_STACK_SIZE ).expect( "idle process creation" );
+        let trap_frame_ptr = idle_process.trap_frame.clone();
+        idle_process.scheduled_at = timer::current_time();
+        self.0.lock().as_mut().expect("scheduler uninitialized").add_idle( core, idle_process, true );
+
+        smp::set_online();
+
+        local_timer::enable( core );
+        local_timer::tick_in( TICK );
+
+        unsafe { context_enter( &trap_frame_ptr, smp::stack_top( core ) ) }
     }
 }
 
+/// Body of the idle kernel thread. Sleeps until the next interrupt, forever.
+extern "C" fn idle(_arg: u64) {
+    use aarch64;
+    loop { aarch64::wfi(); }
+}
+
+/// Restores `tf` and returns from the exception level into it. The kernel
+/// stack pointer is reset to `kernel_sp` since there isn't any other context
+/// to be restored from the stack.
+unsafe fn context_enter(tf: &TrapFrame, kernel_sp: u64) -> ! {
+    // x0 and x30 should also be reset since they are not in context_restore
+    asm!("mov x0, $1
+          mov SP, $0
+          bl context_restore
+          mov SP, x0
+          mov x0, #0
+          mov x30, #0
+          eret"
+         :: "r"(tf as *const TrapFrame), "r"(kernel_sp)
+         : "x0", "x30"
+         : "volatile");
+    loop {}
+}
+
 #[derive(Debug)]
-struct Scheduler {
-    processes: VecDeque<Process>,
-    current: Option<Id>,
-    last_id: Option<Id>,
+pub(super) struct Scheduler {
+    /// Every process, ready, running or blocked, indexed by ID.
+    pub(super) table: ProcessTable,
+    /// Per-core run queues of process IDs. The process running on a core is
+    /// at the front of that core's queue. Blocked processes are only in the
+    /// table.
+    pub(super) run_queues: Vec<VecDeque<Id>>,
+    /// Processes switched out with `State::Dead`, waiting to be dropped.
+    dead: Vec<Process>,
+    /// Trace lines of system calls completed by the scheduler, waiting to be
+    /// logged. `block` leaves them to the next `switch`, since its caller may
+    /// hold the console lock.
+    traces: Vec<String>,
+    current: Vec<Option<Id>>,
+    pub(super) idle: Vec<Option<Id>>,
+    /// The token of the last wait started by a process.
+    waits: u64,
 }
 
 impl Scheduler {
-    /// Returns a new `Scheduler` with an empty queue.
-    fn new() -> Scheduler {
+    /// Returns a new `Scheduler` with empty queues.
+    pub(super) fn new() -> Scheduler {
         Scheduler {
-            processes: VecDeque::new(),
-            current: None,
-            last_id: None,
+            table: ProcessTable::new(),
+            run_queues: (0..smp::NCORES).map( |_| VecDeque::new() ).collect(),
+            dead: Vec::new(),
+            traces: Vec::new(),
+            current: vec![ None; smp::NCORES ],
+            idle: vec![ None; smp::NCORES ],
+            waits: 0,
         }
     }
 
@@ -126,22 +306,74 @@ impl Scheduler {
     /// the process and saved in its `trap_frame`. If no further processes can
     /// be scheduled, returns `None`.
     ///
-    /// If this is the first process added, it is marked as the current process.
-    /// It is the caller's responsibility to ensure that the first time `switch`
-    /// is called, that process is executing on the CPU.
+    /// The process running on the calling core, if any, becomes its parent and
+    /// passes on its resource limits and affinity. The process is queued on
+    /// the least loaded online core of its affinity mask. If this is the first
+    /// process added, it is marked as the current process. It is the caller's
+    /// responsibility to ensure that the first time `switch` is called, that
+    /// process is executing on the CPU. Returns `None` if the parent is at its
+    /// limit of children.
     fn add(&mut self, mut process: Process) -> Option<Id> {
-        let id_generate = match self.last_id {
-            None => 0, //intialization
-            Some(x) => x.wrapping_add( 1 ),
-        };
+        if let Some(x) = self.current[smp::core()].and_then( |p| self.table.get( p ) ) {
+            if !x.can_use( Resource::Children, 1 ) {
+                return None
+            }
+            process.limits = x.limits.clone();
+            process.affinity = x.affinity;
+        }
 
-        self.last_id = Some(id_generate);
-        
-        process.trap_frame.TPIDR = id_generate;
+        let core = self.least_loaded( process.affinity );
+        self.add_to( core, process )
+    }
+
+    /// Allocates an ID for `process` and queues it on `core`. The process
+    /// running on the calling core, if any, becomes its parent.
+    fn add_to(&mut self, core: usize, mut process: Process) -> Option<Id> {
+        let parent = self.current[smp::core()];
+        process.parent = parent;
+
+        let id = self.table.insert( process )?;
+
+        if let Some(x) = parent.and_then( |p| self.table.get_mut( p ) ) {
+            x.children.push( id );
+        }
+
+        self.run_queues[core].push_back( id );
+
+        Some( id )
+    }
+
+    /// Adds `process` as the idle process of `core`, pinned to that core. If
+    /// `running` is `true`, the process is placed at the front of the core's
+    /// queue as the process currently executing on it.
+    fn add_idle(&mut self, core: usize, mut process: Process, running: bool) -> Option<Id> {
+        process.affinity = 1 << core;
+        let id = self.add_to( core, process )?;
+        self.idle[core] = Some( id );
+        if running {
+            self.run_queues[core].pop_back();
+            self.run_queues[core].push_front( id );
+            self.current[core] = Some( id );
+        }
+        Some( id )
+    }
 
-        self.processes.push_back( process );
+    /// Returns the online core in `mask` with the fewest queued processes.
+    pub(super) fn least_loaded(&self, mask: usize) -> usize {
+        (0..smp::NCORES)
+            .filter( |c| smp::is_online( *c ) && mask & ( 1 << *c ) != 0 )
+            .min_by_key( |c| self.run_queues[*c].len() )
+            .unwrap_or( 0 )
+    }
 
-        Some( id_generate )
+    /// Returns the affinity mask of the process with ID `id`.
+    fn affinity(&self, id: Id) -> usize {
+        self.table.get( id ).map_or( smp::ALL_CORES, |x| x.affinity )
+    }
+
+    /// Returns `true` if the process with ID `id` is ready to run.
+    fn is_ready(&mut self, id: Id) -> bool {
+        self.table.get_mut( id ).map_or( false, |x| x.is_ready() )
     }
 
     /// Sets the current process's state to `new_state`, finds the next process
@@ -150,62 +382,268 @@ impl Scheduler {
     /// into `tf`. If there is no current process, returns `None`. Otherwise,
     /// returns `Some` of the process ID that was context switched into `tf`.
     ///
-    /// This method blocks until there is a process to switch to, conserving
-    /// energy as much as possible in the interim.
+    /// Only the run queue of the calling core is considered. A current process
+    /// switched out with `State::Blocked` stays out of the run queues until it
+    /// is unblocked, and one switched out with `State::Dead` or past its CPU
+    /// time limit is removed from the table and moved to `dead` to be dropped
+    /// by the caller. A current process that may no longer run on this core is
+    /// moved to a core in its affinity mask. If no process is ready, a ready
+    /// process allowed on this core is stolen from the busiest other core, and
+    /// failing that the core's idle process is switched in.
     fn switch(&mut self, new_state: State, tf: &mut TrapFrame) -> Option<Id> {
+
+        let core = smp::core();
         
         //current process should at the front of the queue
-        match self.processes.pop_front() {
-            Some(mut x) => {
-
-                self.current = Some( x.trap_frame.TPIDR );
-                
-                x.state = new_state;
-                
-                x.trap_frame = Box::new( *tf ); //save current frame
-                
-                self.processes.push_back(x); //queue current process
+        let id = self.run_queues[core].pop_front()?;
+        self.current[core] = Some( id );
+
+        let ( requeue, dead ) = {
+            let x = self.table.get_mut( id ).expect( "queued process not in table" );
+            x.state = new_state;
+            x.trap_frame = Box::new( *tf ); //save current frame
+
+            //charge the time slice and terminate the process past its limit
+            x.cpu_time += timer::current_time().saturating_sub( x.scheduled_at );
+            if !x.can_use( Resource::CpuTime, 0 ) {
+                x.state = State::Dead;
+            }
+            match x.state {
+                State::Blocked => ( false, false ), //park until woken up
+                State::Dead => ( false, true ),
+                _ => ( true, false ),
+            }
+        };
+
+        if requeue {
+            //queue current process, on another core if it was pinned away from this one
+            let mask = self.affinity( id );
+            let next = if mask & ( 1 << core ) != 0 { core } else { self.least_loaded( mask ) };
+            self.run_queues[next].push_back( id );
+        } else if dead {
+            self.reap( id );
+        }
+
+        //check on queued processes once to find the next process, skipping idle
+        for _ in 0..self.run_queues[core].len() {
+            let x = self.run_queues[core].pop_front().unwrap();
+
+            if Some( x ) != self.idle[core] && self.is_ready( x ) {
+                return Some( self.run( core, x, tf ) )
+            }
+
+            self.run_queues[core].push_back( x );
+        }
+
+        //nothing is ready locally, balance load by pulling work from another core
+        if let Some(x) = self.steal( core ) {
+            return Some( self.run( core, x, tf ) )
+        }
+
+        //nothing is ready, fall back to the idle process, which never blocks
+        //and so is queued whenever it is not current
+        let idle = self.idle[core].expect( "core without an idle process" );
+        let i = self.run_queues[core].iter().position( |x| *x == idle ).expect( "idle process not queued" );
+        self.run_queues[core].remove( i );
+        Some( self.run( core, idle, tf ) )
+    }
+
+    /// Removes the dead process `id` from the table and moves it to `dead`.
+    /// Its children are orphaned, and its parent is sent `SIGCHLD`.
+    fn reap(&mut self, id: Id) {
+        use process::signal::SIGCHLD;
+
+        let x = match self.table.remove( id ) {
+            Some(x) => x,
+            None => return,
+        };
+
+        for c in x.children.iter() {
+            if let Some(child) = self.table.get_mut( *c ) {
+                child.parent = None;
+            }
+        }
+
+        let interrupted = match x.parent.and_then( |p| self.table.get_mut( p ) ) {
+            Some(parent) => {
+                parent.children.retain( |c| *c != id );
+                parent.signals.raise( SIGCHLD );
+                parent.signals.is_interrupted()
             },
-            None => { return None },  
+            None => false,
+        };
+        if interrupted {
+            self.interrupt( x.parent.unwrap() );
         }
 
-        //check on queued processes to find the next process
-        loop {
-            match self.processes.pop_front() {
-                Some(mut x) => {
+        self.dead.push( x );
+    }
 
-                    if x.is_ready() {
+    /// Removes and returns the ID of a ready process allowed on `core` from the
+    /// busiest core other than `core`. Neither the process running on that
+    /// core nor its idle process is taken.
+    pub(super) fn steal(&mut self, core: usize) -> Option<Id> {
+        let victim = (0..smp::NCORES)
+            .filter( |c| *c != core && self.run_queues[*c].len() > 2 )
+            .max_by_key( |c| self.run_queues[*c].len() )?;
 
-                        self.current = Some( x.trap_frame.TPIDR );
+        let idle = self.idle[victim];
+        for i in (1..self.run_queues[victim].len()).rev() {
+            let x = self.run_queues[victim][i];
+            if Some( x ) != idle && self.affinity( x ) & ( 1 << core ) != 0 && self.is_ready( x ) {
+                return self.run_queues[victim].remove( i )
+            }
+        }
+        None
+    }
 
-                        //get the frame of the process
-                        *tf = *x.trap_frame;
-                        
-                        x.state = State::Running;
+    /// Restores the trap frame of the process `id` into `tf` and leaves it at
+    /// the front of the queue of `core` as the running process. Returns `id`.
+    fn run(&mut self, core: usize, id: Id, tf: &mut TrapFrame) -> Id {
+        self.current[core] = Some( id );
 
-                        self.processes.push_front(x); //leave it in the front until the next interrupt
+        {
+            let x = self.table.get_mut( id ).expect( "queued process not in table" );
 
-                        return self.current
+            //get the frame of the process
+            *tf = *x.trap_frame;
 
-                    } else {
-                        
-                        match self.current {
-                            Some(c) if c == x.trap_frame.TPIDR => {
-                                //sleep
-                                use aarch64;
-                                aarch64::wfi();
-                            },
-                            _ => {},
-                        }
-                        
-                        self.processes.push_back(x);
-                        //retry
-                    }
-                },
-                _ => {
-                    return None
+            x.state = State::Running;
+            x.scheduled_at = timer::current_time();
+
+            if x.traced {
+                TRACED.fetch_or( 1 << core, Ordering::Relaxed );
+            } else {
+                TRACED.fetch_and( !( 1 << core ), Ordering::Relaxed );
+            }
+            if let Some(line) = x.trace.take() {
+                self.traces.push( line );
+            }
+        }
+
+        self.run_queues[core].push_front( id ); //leave it in the front until the next interrupt
+
+        id
+    }
+
+    /// Returns a snapshot of every process, in ID order.
+    fn snapshot(&self) -> Vec<ProcessInfo> {
+        self.table.iter().map( |x| {
+            let id = x.trap_frame.TPIDR;
+            ProcessInfo {
+                id: id,
+                parent: x.parent,
+                name: x.name.clone(),
+                state: format!( "{:?}", x.state ),
+                core: self.run_queues.iter().position( |q| q.contains( &id ) ),
+                stack_used: x.stack.used(),
+                stack_size: x.stack.size(),
+                cpu_time: x.usage( Resource::CpuTime ),
+            }
+        }).collect()
+    }
+
+    /// Returns the process with ID `id`.
+    fn find_mut(&mut self, id: Id) -> Option<&mut Process> {
+        self.table.get_mut( id )
+    }
+
+    /// Starts a new wait of the process with ID `id`, about to be blocked.
+    fn wait(&mut self, id: Id) -> Waiter {
+        self.waits += 1;
+        if let Some(x) = self.table.get_mut( id ) {
+            x.wait = self.waits;
+        }
+        Waiter { id, token: self.waits }
+    }
+
+    /// Unblocks the process of `w` if it is still blocked in the wait `w` was
+    /// returned for. Returns `false` otherwise.
+    fn wake(&mut self, w: Waiter) -> bool {
+        match self.table.get( w.id ) {
+            Some(x) if x.wait == w.token => {},
+            _ => return false,
+        }
+        self.unblock( w.id )
+    }
+
+    /// Unblocks the process with ID `id` from whatever it is blocked in, so
+    /// that a signal can be delivered. The interrupted system call fails with
+    /// `Interrupted`, unless it was set up to be executed again, like a pipe
+    /// transfer. Returns `false` if the process is not blocked.
+    fn interrupt(&mut self, id: Id) -> bool {
+        if !self.unblock( id ) {
+            return false
+        }
+        if let Some(x) = self.table.get_mut( id ) {
+            x.trap_frame.x1_x29[6] = OsError::Interrupted.code(); //status in x7
+        }
+        true
+    }
+
+    /// Moves the blocked process with ID `id` to the back of the ready queue of
+    /// the least loaded core it may run on. Returns `false` if the process is not blocked.
+    fn unblock(&mut self, id: Id) -> bool {
+        let blocked = match self.table.get_mut( id ) {
+            Some(x) => match x.state {
+                State::Blocked => {
+                    x.state = State::Ready;
+                    true
                 },
-            }   
+                _ => false,
+            },
+            None => false,
+        };
+
+        if blocked {
+            let core = self.least_loaded( self.affinity( id ) );
+            self.run_queues[core].push_back( id );
+        }
+        blocked
+    }
+
+    /// Sets the affinity mask of the process with ID `id` to `mask` and
+    /// returns the previous mask. A queued process that may no longer run on
+    /// its core is moved right away; a running one is moved when it is
+    /// switched out.
+    pub(super) fn set_affinity(&mut self, id: Id, mask: usize) -> Result<usize, AffinityError> {
+        use std::mem;
+
+        if mask & smp::online() == 0 {
+            return Err( AffinityError::NoOnlineCore )
+        }
+        if self.idle.contains( &Some( id ) ) {
+            return Err( AffinityError::Idle )
+        }
+
+        let old = match self.table.get_mut( id ) {
+            Some(x) => mem::replace( &mut x.affinity, mask ),
+            None => return Err( AffinityError::NoSuchProcess ),
+        };
+
+        for core in (0..smp::NCORES).filter( |c| mask & ( 1 << *c ) == 0 ) {
+            //the front of a queue is the running process
+            let queued = self.run_queues[core].iter().skip( 1 ).position( |x| *x == id );
+            if let Some(i) = queued {
+                self.run_queues[core].remove( i + 1 );
+                let next = self.least_loaded( mask );
+                self.run_queues[next].push_back( id );
+            }
+        }
+
+        Ok( old )
+    }
+
+    /// Returns `true` if the process with ID `id` is the process with ID
+    /// `ancestor` or one of its descendants.
+    pub(super) fn is_descendant(&self, id: Id, ancestor: Id) -> bool {
+        let mut x = Some( id );
+        while let Some(p) = x {
+            if p == ancestor {
+                return true
+            }
+            x = self.table.get( p ).and_then( |y| y.parent );
         }
+        false
     }
 }
@@ -0,0 +1,270 @@
+use std::fmt;
+use std::mem;
+use std::ptr;
+
+use allocator::util::align_down;
+use process::{State, Id, UserPtr};
+use traps::TrapFrame;
+use SCHEDULER;
+
+pub use abi::signal::{Signal, NSIG, SIGINT, SIGKILL, SIGUSR1, SIGUSR2, SIGTERM, SIGCHLD};
+
+/// What a process does when it receives a signal.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    /// The default action of the signal: `SIGCHLD` is ignored, every other
+    /// signal terminates the process.
+    Default,
+    /// The signal is discarded.
+    Ignore,
+    /// The signal is handled by the user function at `entry`, which returns
+    /// to `restorer`. `restorer` must issue the `sigreturn` system call.
+    Handler { entry: u64, restorer: u64 },
+}
+
+/// The effective action for a signal being delivered.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Disposition {
+    Ignore,
+    Terminate,
+    Handle { entry: u64, restorer: u64 },
+}
+
+/// The signal state of a process: signals raised but not yet delivered,
+/// signals whose delivery is deferred, and the action of every signal.
+pub struct Signals {
+    pending: u32,
+    blocked: u32,
+    actions: [Action; NSIG as usize],
+}
+
+/// Returns the bit of `sig` in a signal mask.
+fn bit(sig: Signal) -> u32 {
+    1 << sig
+}
+
+/// Returns `true` if `sig` is a valid signal number.
+pub fn is_valid(sig: Signal) -> bool {
+    sig > 0 && sig < NSIG
+}
+
+impl Signals {
+    /// Returns the state of a new process: nothing pending or blocked and the
+    /// default action for every signal.
+    pub fn new() -> Signals {
+        Signals {
+            pending: 0,
+            blocked: 0,
+            actions: [Action::Default; NSIG as usize],
+        }
+    }
+
+    /// Marks `sig` pending. Returns `false` if `sig` is not a valid signal.
+    pub fn raise(&mut self, sig: Signal) -> bool {
+        if !is_valid( sig ) {
+            return false
+        }
+        self.pending |= bit( sig );
+        true
+    }
+
+    /// Returns the mask of pending signals.
+    pub fn pending(&self) -> u32 {
+        self.pending
+    }
+
+    /// Returns the mask of blocked signals.
+    pub fn blocked(&self) -> u32 {
+        self.blocked
+    }
+
+    /// Sets the mask of blocked signals. `SIGKILL` cannot be blocked.
+    pub fn set_blocked(&mut self, mask: u32) {
+        self.blocked = mask & !bit( SIGKILL ) & !1;
+    }
+
+    /// Returns the action of `sig`.
+    pub fn action(&self, sig: Signal) -> Option<Action> {
+        if is_valid( sig ) {
+            Some( self.actions[sig as usize] )
+        } else {
+            None
+        }
+    }
+
+    /// Sets the action of `sig` and returns the previous one. Returns `None`
+    /// if `sig` is invalid or is `SIGKILL`, whose action cannot be changed.
+    pub fn set_action(&mut self, sig: Signal, action: Action) -> Option<Action> {
+        if !is_valid( sig ) || sig == SIGKILL {
+            return None
+        }
+        Some( mem::replace( &mut self.actions[sig as usize], action ) )
+    }
+
+    fn disposition(&self, sig: Signal) -> Disposition {
+        match self.actions[sig as usize] {
+            Action::Default if sig == SIGCHLD => Disposition::Ignore,
+            Action::Default => Disposition::Terminate,
+            Action::Ignore => Disposition::Ignore,
+            Action::Handler { entry, restorer } => Disposition::Handle { entry, restorer },
+        }
+    }
+
+    /// Returns `true` if a signal that is pending and not blocked would
+    /// terminate the process or run a handler. Processes waiting in the kernel
+    /// are interrupted by such signals.
+    pub fn is_interrupted(&self) -> bool {
+        let deliverable = self.pending & !self.blocked;
+        (1..NSIG).any( |sig| deliverable & bit( sig ) != 0 && self.disposition( sig ) != Disposition::Ignore )
+    }
+
+    /// Removes the lowest pending signal that is not blocked and is not
+    /// ignored, discarding ignored signals on the way.
+    fn next(&mut self) -> Option<(Signal, Disposition)> {
+        loop {
+            let deliverable = self.pending & !self.blocked;
+            if deliverable == 0 {
+                return None
+            }
+
+            let sig = deliverable.trailing_zeros();
+            self.pending &= !bit( sig );
+            match self.disposition( sig ) {
+                Disposition::Ignore => continue,
+                d => return Some( ( sig, d ) ),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Signals {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Signals")
+            .field("pending", &format_args!("{:#x}", self.pending))
+            .field("blocked", &format_args!("{:#x}", self.blocked))
+            .finish()
+    }
+}
+
+/// The context saved on the user stack while a signal handler runs, restored
+/// by `sigreturn`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct SignalFrame {
+    /// The trap frame of the interrupted process.
+    pub tf: TrapFrame,
+    /// The blocked signal mask before the handler was entered.
+    pub blocked: u32,
+}
+
+/// Returns `true` if restoring `tf` returns to EL0.
+fn returns_to_el0(tf: &TrapFrame) -> bool {
+    tf.SPSR & 0b1111 == 0
+}
+
+/// Raises `sig` in the process with ID `id`. If the signal interrupts the
+/// process and it is blocked in the kernel, it is made ready so that the
+/// signal can be delivered. Returns `false` if there is no such process or
+/// `sig` is invalid.
+///
+/// Kernel threads never return to EL0, so signals raised in them stay
+/// pending.
+pub fn kill(id: Id, sig: Signal) -> bool {
+    match SCHEDULER.with_process( id, |p| p.signals.raise( sig ) && p.signals.is_interrupted() ) {
+        Some( interrupted ) => {
+            if interrupted {
+                //a blocked system call fails with `Interrupted`, or is executed
+                //again after the handler if it was set up to be
+                SCHEDULER.interrupt( id );
+            }
+            is_valid( sig )
+        },
+        None => false,
+    }
+}
+
+/// Delivers the pending signals of the process about to be restored from `tf`
+/// if it returns to EL0. Called on every exception return.
+///
+/// A signal with a handler pushes a `SignalFrame` on the user stack and
+/// enters the handler with the signal number in `x0` and `x30` pointing to the
+/// restorer. The signal is blocked while its handler runs. A signal that
+/// terminates the process switches `tf` to the next process, whose signals
+/// are then delivered in turn.
+pub fn deliver(tf: &mut TrapFrame) {
+    use console::kprintln;
+
+    while returns_to_el0( tf ) {
+        let next = SCHEDULER.with_process( tf.TPIDR, |p| {
+            let ( sig, d ) = p.signals.next()?;
+            let blocked = p.signals.blocked();
+            if let Disposition::Handle { .. } = d {
+                p.signals.set_blocked( blocked | bit( sig ) );
+            }
+            Some( ( sig, d, blocked, p.stack.bottom().as_u64() ) )
+        });
+
+        let ( sig, d, blocked, stack_bottom ) = match next {
+            Some(Some(x)) => x,
+            _ => return,
+        };
+
+        match d {
+            Disposition::Handle { entry, restorer } => {
+                let sp = align_down( ( tf.SP as usize ).wrapping_sub( mem::size_of::<SignalFrame>() ), 16 );
+                if ( sp as u64 ) < stack_bottom || sp as u64 > tf.SP {
+                    kprintln!( "process {}: no stack space for signal {}", tf.TPIDR, sig );
+                    terminate( tf );
+                    continue
+                }
+
+                unsafe { ptr::write( sp as *mut SignalFrame, SignalFrame { tf: *tf, blocked } ); }
+                tf.SP = sp as u64;
+                tf.ELR = entry;
+                tf.x0 = sig as u64;
+                tf.x30 = restorer;
+                return
+            },
+            _ => {
+                kprintln!( "process {}: terminated by signal {}", tf.TPIDR, sig );
+                terminate( tf );
+            },
+        }
+    }
+}
+
+/// Switches `tf` from the current process, which is terminated, to the next
+/// process.
+fn terminate(tf: &mut TrapFrame) {
+    if SCHEDULER.switch( State::Dead, tf ).is_none() {
+        panic!( "no process to switch to after terminating process {}", tf.TPIDR );
+    }
+}
+
+/// Restores the context saved by `deliver` from the `SignalFrame` at the
+/// stack pointer of `tf`. The restored frame always returns to EL0, and the
+/// process ID cannot be changed. A process whose stack pointer does not point
+/// to a frame in its memory is terminated.
+pub fn restore(tf: &mut TrapFrame) {
+    use console::kprintln;
+
+    let frame = SCHEDULER.with_process( tf.TPIDR, |p| {
+        let frame = UserPtr::<SignalFrame>::new( tf.SP ).read( p ).ok()?;
+        p.signals.set_blocked( frame.blocked );
+        Some( frame )
+    });
+
+    let frame = match frame {
+        Some(Some(x)) => x,
+        _ => {
+            kprintln!( "process {}: bad signal frame at {:#x}", tf.TPIDR, tf.SP );
+            terminate( tf );
+            return
+        },
+    };
+
+    let mut restored = frame.tf;
+    restored.SPSR &= 0xf000_0000; //only the condition flags: EL0t, AArch64, DAIF clear
+    restored.TPIDR = tf.TPIDR;
+    *tf = restored;
+}
@@ -1,13 +1,15 @@
 use std::fmt;
 use std::ptr::Unique;
 
-use ALLOCATOR;
 use alloc::allocator::{Alloc, Layout};
+use alloc::heap::Heap;
+use allocator::util::align_up;
 use vm::PhysicalAddr;
 
-/// A process stack. The default size is 1M1B with an alignment of 16 bytes.
+/// A process stack. The default size is 1MiB with an alignment of 16 bytes.
 pub struct Stack {
-    ptr: Unique<[u8; Stack::SIZE]>
+    ptr: Unique<u8>,
+    size: usize,
 }
 
 impl Stack {
@@ -17,45 +19,67 @@ impl Stack {
     /// The default stack alignment is 16 bytes.
     pub const ALIGN: usize = 16;
 
-    /// The default layout for a stack.
-    fn layout() -> Layout {
-        unsafe { Layout::from_size_align_unchecked(Self::SIZE, Self::ALIGN) }
+    /// The layout for a stack of `size` bytes.
+    fn layout(size: usize) -> Layout {
+        unsafe { Layout::from_size_align_unchecked(size, Self::ALIGN) }
     }
 
-    /// Returns a newly allocated process stack, zeroed out, if one could be
-    /// successfully allocated. If there is no memory, or memory allocation
-    /// fails for some other reason, returns `None`.
-    pub fn new() -> Option<Stack> {
+    /// Returns a newly allocated process stack of at least `size` bytes,
+    /// zeroed out, if one could be successfully allocated. The size is rounded
+    /// up to a multiple of `Stack::ALIGN`. If there is no memory, or memory
+    /// allocation fails for some other reason, returns `None`.
+    pub fn new(size: usize) -> Option<Stack> {
+        let size = align_up( size.max( Self::ALIGN ), Self::ALIGN );
+
         let raw_ptr = unsafe {
-            let raw_ptr: *mut u8 = (&ALLOCATOR).alloc(Stack::layout()).ok()?;
-            raw_ptr.write_bytes(0, Self::SIZE);
+            let raw_ptr: *mut u8 = Heap.alloc(Stack::layout(size)).ok()?;
+            raw_ptr.write_bytes(0, size);
             raw_ptr
         };
 
-        let ptr = Unique::new(raw_ptr as *mut _).expect("non-null");
-        Some(Stack { ptr })
+        let ptr = Unique::new(raw_ptr).expect("non-null");
+        Some(Stack { ptr, size })
     }
 
     /// Internal method to cast to a `*mut u8`.
     unsafe fn as_mut_ptr(&self) -> *mut u8 {
-        self.ptr.as_ptr() as _
+        self.ptr.as_ptr()
     }
 
     /// Returns the physical address of top of the stack.
     pub fn top(&self) -> PhysicalAddr {
-        unsafe { self.as_mut_ptr().add(Self::SIZE).into() }
+        unsafe { self.as_mut_ptr().add(self.size).into() }
     }
 
     /// Returns the physical address of bottom of the stack.
     pub fn bottom(&self) -> PhysicalAddr {
         unsafe { self.as_mut_ptr().into() }
     }
+
+    /// Returns the size of the stack in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of bytes of the stack that have been used so far.
+    ///
+    /// The stack grows down from `top()` and is zeroed on allocation, so this
+    /// is the distance from the top to the lowest byte that is no longer
+    /// zero. Bytes that were written with zero are not detected, making this
+    /// a lower bound of the high-water mark.
+    pub fn used(&self) -> usize {
+        let bytes = unsafe { ::std::slice::from_raw_parts(self.as_mut_ptr(), self.size) };
+        match bytes.iter().position(|b| *b != 0) {
+            Some(untouched) => self.size - untouched,
+            None => 0,
+        }
+    }
 }
 
 impl Drop for Stack {
     fn drop(&mut self) {
         unsafe {
-            (&ALLOCATOR).dealloc(self.as_mut_ptr(), Self::layout())
+            Heap.dealloc(self.as_mut_ptr(), Self::layout(self.size))
         }
     }
 }
@@ -65,7 +89,7 @@ impl fmt::Debug for Stack {
         f.debug_struct("Stack")
             .field("top", &self.top())
             .field("bottom", &self.bottom())
-            .field("size", &Self::SIZE)
+            .field("size", &self.size)
             .finish()
     }
 }
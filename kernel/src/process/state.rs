@@ -15,8 +15,14 @@ pub enum State {
     Ready,
     /// The process is waiting on an event to occur before it can be scheduled.
     Waiting(EventPollFn),
+    /// The process is blocked on a `WaitQueue` and is kept out of the ready
+    /// queue until it is woken up.
+    Blocked,
     /// The process is currently running.
     Running,
+    /// The process has exited. It is removed from the scheduler and its
+    /// resources are released when it is switched out.
+    Dead,
 }
 
 impl fmt::Debug for State {
@@ -25,6 +31,8 @@ impl fmt::Debug for State {
             State::Ready => write!(f, "State::Ready"),
             State::Running => write!(f, "State::Running"),
             State::Waiting(_) => write!(f, "State::Waiting"),
+            State::Blocked => write!(f, "State::Blocked"),
+            State::Dead => write!(f, "State::Dead"),
         }
     }
 }
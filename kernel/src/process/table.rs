@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use process::{Process, Id};
+
+/// Maximum number of processes that can exist at once.
+pub const MAX_PROCESSES: usize = 1024;
+
+/// Number of freed IDs kept out of circulation. A freed ID is reused only
+/// after this many other IDs were freed after it, so that a stale ID held by
+/// some process is unlikely to refer to a new process right away.
+pub const REUSE_DELAY: usize = 16;
+
+/// Allocator of process IDs from `0` to `max - 1`.
+///
+/// New IDs are handed out in increasing order. Freed IDs are queued and
+/// reused oldest first once more than `REUSE_DELAY` of them are queued, or
+/// once every ID was handed out at least once.
+#[derive(Debug)]
+pub struct IdAllocator {
+    next: Id,
+    max: usize,
+    free: VecDeque<Id>,
+}
+
+impl IdAllocator {
+    /// Returns an allocator of the IDs `0` to `max - 1`.
+    pub fn new(max: usize) -> IdAllocator {
+        IdAllocator { next: 0, max, free: VecDeque::new() }
+    }
+
+    /// Returns an unused ID, or `None` if all `max` IDs are in use.
+    pub fn alloc(&mut self) -> Option<Id> {
+        if self.free.len() > REUSE_DELAY || self.next as usize == self.max {
+            return self.free.pop_front()
+        }
+        let id = self.next;
+        self.next += 1;
+        Some( id )
+    }
+
+    /// Returns `id` to the allocator.
+    pub fn free(&mut self, id: Id) {
+        self.free.push_back( id );
+    }
+}
+
+/// Every process in the system, indexed by ID.
+#[derive(Debug)]
+pub struct ProcessTable {
+    ids: IdAllocator,
+    slots: Vec<Option<Process>>,
+    len: usize,
+}
+
+impl ProcessTable {
+    /// Returns an empty table of up to `MAX_PROCESSES` processes.
+    pub fn new() -> ProcessTable {
+        ProcessTable {
+            ids: IdAllocator::new( MAX_PROCESSES ),
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Allocates an ID for `process`, saves it in its trap frame and stores
+    /// the process under it. Returns `None` if the table is full.
+    pub fn insert(&mut self, mut process: Process) -> Option<Id> {
+        let id = self.ids.alloc()?;
+        process.trap_frame.TPIDR = id;
+
+        while self.slots.len() <= id as usize {
+            self.slots.push( None );
+        }
+        self.slots[id as usize] = Some( process );
+        self.len += 1;
+        Some( id )
+    }
+
+    /// Returns the process with ID `id`.
+    pub fn get(&self, id: Id) -> Option<&Process> {
+        self.slots.get( id as usize )?.as_ref()
+    }
+
+    /// Returns the process with ID `id`.
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut Process> {
+        self.slots.get_mut( id as usize )?.as_mut()
+    }
+
+    /// Removes and returns the process with ID `id`, freeing its ID.
+    pub fn remove(&mut self, id: Id) -> Option<Process> {
+        let process = self.slots.get_mut( id as usize )?.take()?;
+        self.ids.free( id );
+        self.len -= 1;
+        Some( process )
+    }
+
+    /// Returns an iterator over every process in ID order.
+    pub fn iter<'a>(&'a self) -> Box<Iterator<Item = &'a Process> + 'a> {
+        Box::new( self.slots.iter().filter_map( |x| x.as_ref() ) )
+    }
+
+    /// Returns the number of processes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+}
@@ -0,0 +1,297 @@
+mod id_allocator {
+    use process::table::{IdAllocator, REUSE_DELAY};
+
+    #[test]
+    fn increasing_ids() {
+        let mut ids = IdAllocator::new(8);
+        for i in 0..8 {
+            assert_eq!(ids.alloc(), Some(i));
+        }
+        assert_eq!(ids.alloc(), None);
+    }
+
+    #[test]
+    fn freed_ids_are_not_reused_right_away() {
+        let mut ids = IdAllocator::new(1024);
+        for i in 0..4 {
+            assert_eq!(ids.alloc(), Some(i));
+        }
+
+        ids.free(2);
+        assert_eq!(ids.alloc(), Some(4));
+        ids.free(4);
+        assert_eq!(ids.alloc(), Some(5));
+    }
+
+    #[test]
+    fn freed_ids_are_reused_oldest_first() {
+        let n = REUSE_DELAY as u64 + 2;
+        let mut ids = IdAllocator::new(1024);
+        for i in 0..n {
+            assert_eq!(ids.alloc(), Some(i));
+        }
+        for i in 0..n {
+            ids.free(i);
+        }
+
+        assert_eq!(ids.alloc(), Some(0));
+        assert_eq!(ids.alloc(), Some(1));
+        //back to the delay
+        assert_eq!(ids.alloc(), Some(n));
+        ids.free(n);
+        assert_eq!(ids.alloc(), Some(2));
+    }
+
+    #[test]
+    fn exhausted_ids_reuse_any_freed_id() {
+        let mut ids = IdAllocator::new(4);
+        for i in 0..4 {
+            assert_eq!(ids.alloc(), Some(i));
+        }
+
+        ids.free(3);
+        ids.free(1);
+        assert_eq!(ids.alloc(), Some(3));
+        assert_eq!(ids.alloc(), Some(1));
+        assert_eq!(ids.alloc(), None);
+    }
+}
+
+mod limits {
+    use process::{Limits, Resource, UNLIMITED};
+
+    #[test]
+    fn limits_are_inclusive() {
+        let limits = Limits::new();
+        let files = limits.get(Resource::Files);
+        assert!(limits.allows(Resource::Files, files));
+        assert!(!limits.allows(Resource::Files, files + 1));
+        assert!(limits.allows(Resource::CpuTime, UNLIMITED));
+    }
+
+    #[test]
+    fn limits_can_only_be_lowered() {
+        let mut limits = Limits::new();
+        assert_eq!(limits.set(Resource::CpuTime, 500), Some(UNLIMITED));
+        assert_eq!(limits.set(Resource::CpuTime, 1000), None);
+        assert_eq!(limits.get(Resource::CpuTime), 500);
+        assert_eq!(limits.set(Resource::CpuTime, 0), Some(500));
+        assert!(!limits.allows(Resource::CpuTime, 1));
+    }
+
+    #[test]
+    fn resource_numbers() {
+        assert_eq!(Resource::from_u64(0), Some(Resource::Memory));
+        assert_eq!(Resource::from_u64(3), Some(Resource::CpuTime));
+        assert_eq!(Resource::from_u64(4), None);
+    }
+}
+
+mod user {
+    use abi::OsError;
+    use process::user::{within, RODATA};
+    use process::{Access, Process, UserPtr, UserSlice};
+
+    static mut BSS: [u64; 4] = [0; 4];
+
+    #[test]
+    fn ranges_inside() {
+        assert!(within(0x1000, 0x10, 0x1000, 0x100));
+        assert!(within(0x10f0, 0x10, 0x1000, 0x100));
+        assert!(within(0x1000, 0, 0x1000, 0x100));
+    }
+
+    #[test]
+    fn ranges_outside() {
+        assert!(!within(0xff0, 0x10, 0x1000, 0x100));
+        assert!(!within(0x10f8, 0x10, 0x1000, 0x100));
+        assert!(!within(0x2000, 1, 0x1000, 0x100));
+    }
+
+    #[test]
+    fn wrapping_ranges_are_outside() {
+        assert!(!within(u64::max_value() - 4, 16, 0, usize::max_value()));
+        assert!(!within(0x1000, usize::max_value(), 0x1000, 0x100));
+    }
+
+    #[test]
+    fn writes_into_the_image_are_bad_addresses() {
+        let p = Process::new(4096).unwrap();
+        let bss = unsafe { BSS.as_ptr() as u64 };
+        let e: OsError = UserPtr::<u64>::new(bss).write(&p, 1).unwrap_err().into();
+        assert_eq!(e, OsError::BadAddress);
+        assert_eq!(unsafe { BSS[0] }, 0);
+        assert!(UserSlice::new(bss, 8).check(&p, Access::Read).is_err());
+    }
+
+    #[test]
+    fn rodata_is_read_only() {
+        let p = Process::new(4096).unwrap();
+        let rodata = UserSlice::new(RODATA.as_ptr() as u64, RODATA.len() as u64);
+        assert_eq!(rodata.to_vec(&p), Ok(vec![0; 64]));
+        assert!(rodata.copy_to_user(&p, &[1]).is_err());
+    }
+
+    #[test]
+    fn stack_is_read_write() {
+        let p = Process::new(4096).unwrap();
+        let top = p.stack.top().as_u64() - 8;
+        assert_eq!(UserPtr::<u64>::new(top).write(&p, 7), Ok(()));
+        assert_eq!(UserPtr::<u64>::new(top).read(&p), Ok(7));
+    }
+}
+
+mod scheduler {
+    use process::scheduler::Scheduler;
+    use process::{AffinityError, Id, Process};
+    use smp::{self, ALL_CORES};
+
+    /// Returns a scheduler with every core online and `lens[n]` processes
+    /// queued on core `n`, the first of which is the running one.
+    fn scheduler(lens: &[usize]) -> Scheduler {
+        smp::set_online_mask(ALL_CORES);
+        let mut s = Scheduler::new();
+        for (core, &len) in lens.iter().enumerate() {
+            for _ in 0..len {
+                queue(&mut s, core, ALL_CORES);
+            }
+        }
+        s
+    }
+
+    /// Queues a new ready process that may run on the cores in `mask` on
+    /// `core`.
+    fn queue(s: &mut Scheduler, core: usize, mask: usize) -> Id {
+        let mut p = Process::new(4096).unwrap();
+        p.affinity = mask;
+        let id = s.table.insert(p).unwrap();
+        s.run_queues[core].push_back(id);
+        id
+    }
+
+    #[test]
+    fn least_loaded_core_in_mask() {
+        let s = scheduler(&[3, 1, 2, 0]);
+        assert_eq!(s.least_loaded(ALL_CORES), 3);
+        assert_eq!(s.least_loaded(0b0111), 1);
+        assert_eq!(s.least_loaded(0b0101), 2);
+        assert_eq!(s.least_loaded(0b0001), 0);
+    }
+
+    #[test]
+    fn steal_takes_a_process_allowed_on_the_core() {
+        let mut s = scheduler(&[0, 1]);
+        let free = queue(&mut s, 1, ALL_CORES);
+        queue(&mut s, 1, 0b0010);
+
+        assert_eq!(s.steal(0), Some(free));
+        assert_eq!(s.run_queues[1].len(), 2);
+        assert_eq!(s.steal(0), None);
+    }
+
+    #[test]
+    fn steal_leaves_running_and_idle_processes() {
+        let mut s = scheduler(&[0, 1]);
+        let idle = queue(&mut s, 1, ALL_CORES);
+        s.idle[1] = Some(idle);
+        queue(&mut s, 1, 0b0010);
+
+        assert_eq!(s.steal(0), None);
+        assert_eq!(s.run_queues[1].len(), 3);
+    }
+
+    #[test]
+    fn set_affinity_moves_queued_processes() {
+        let mut s = scheduler(&[1]);
+        let running = s.run_queues[0][0];
+        let queued = queue(&mut s, 0, ALL_CORES);
+
+        assert_eq!(s.set_affinity(queued, 0b0100), Ok(ALL_CORES));
+        assert_eq!(s.run_queues[0].iter().cloned().collect::<Vec<_>>(), vec![running]);
+        assert_eq!(s.run_queues[2].iter().cloned().collect::<Vec<_>>(), vec![queued]);
+
+        //the running process moves when it is switched out
+        assert_eq!(s.set_affinity(running, 0b0010), Ok(ALL_CORES));
+        assert_eq!(s.run_queues[0][0], running);
+        assert_eq!(s.table.get(running).unwrap().affinity, 0b0010);
+    }
+
+    #[test]
+    fn set_affinity_errors() {
+        let mut s = scheduler(&[2]);
+        let (idle, other) = (s.run_queues[0][0], s.run_queues[0][1]);
+        s.idle[0] = Some(idle);
+
+        assert_eq!(s.set_affinity(other, 0), Err(AffinityError::NoOnlineCore));
+        assert_eq!(s.set_affinity(idle, 0b0010), Err(AffinityError::Idle));
+        assert_eq!(s.set_affinity(999, 0b0001), Err(AffinityError::NoSuchProcess));
+        assert_eq!(s.table.get(other).unwrap().affinity, ALL_CORES);
+    }
+
+    #[test]
+    fn descendants() {
+        let mut s = scheduler(&[3]);
+        let ids: Vec<Id> = s.run_queues[0].iter().cloned().collect();
+        s.table.get_mut(ids[1]).unwrap().parent = Some(ids[0]);
+        s.table.get_mut(ids[2]).unwrap().parent = Some(ids[1]);
+
+        assert!(s.is_descendant(ids[2], ids[0]));
+        assert!(s.is_descendant(ids[1], ids[1]));
+        assert!(!s.is_descendant(ids[0], ids[2]));
+        assert!(!s.is_descendant(999, ids[0]));
+    }
+}
+
+mod loader {
+    use abi::{image, OsError};
+    use process::{self, Descriptor, Access};
+
+    /// Returns a binary of `len` bytes, counting up from its header, whose
+    /// image takes `size` bytes in memory.
+    fn program(len: usize, size: u64) -> Vec<u8> {
+        let mut bin: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        bin[4..8].copy_from_slice(&image::MAGIC);
+        for i in 0..8 {
+            bin[8 + i] = (size >> (8 * i)) as u8;
+        }
+        bin
+    }
+
+    #[test]
+    fn runs_at_el0_from_the_start_of_its_image() {
+        let bin = program(64, 0x3000);
+        let p = process::load(&bin, "hello", &["a", "b"]).unwrap();
+
+        assert_eq!(p.name, "hello");
+        assert_eq!(p.args, vec!["a", "b"]);
+        assert!(!p.is_kernel());
+        assert_eq!(p.trap_frame.SP, p.stack.top().as_u64());
+        for fd in 0..3 {
+            assert!(match p.files.get(fd) { Some(&Descriptor::Console) => true, _ => false });
+        }
+
+        let entry = p.trap_frame.ELR;
+        assert_eq!(p.regions.len(), 1);
+        assert_eq!(p.regions[0].start().as_u64(), entry);
+        assert!(p.owns(entry, 0x3000, Access::Write));
+
+        let mem = unsafe { ::std::slice::from_raw_parts(entry as *const u8, 0x3000) };
+        assert_eq!(&mem[..64], &bin[..]);
+        assert!(mem[64..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut bin = program(64, 0x3000);
+        bin[4] = b'X';
+        assert_eq!(process::load(&bin, "x", &[]).err(), Some(OsError::InvalidArgument));
+        assert_eq!(process::load(&bin[..8], "x", &[]).err(), Some(OsError::InvalidArgument));
+        assert_eq!(process::load(&program(64, 32), "x", &[]).err(), Some(OsError::InvalidArgument));
+    }
+
+    #[test]
+    fn counts_against_the_memory_limit() {
+        let bin = program(64, 1 << 40);
+        assert_eq!(process::load(&bin, "x", &[]).err(), Some(OsError::NoMemory));
+    }
+}
@@ -0,0 +1,157 @@
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+use abi::OsError;
+use process::Process;
+
+#[cfg(not(test))]
+extern "C" {
+    static _start: u8;
+    static _erodata: u8;
+}
+
+/// A system call argument points outside of the memory of the calling
+/// process.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Fault;
+
+impl From<Fault> for OsError {
+    fn from(_: Fault) -> OsError {
+        OsError::BadAddress
+    }
+}
+
+/// Returns `true` if the `len` bytes at `addr` lie within the `size` bytes at
+/// `start`. Ranges that wrap around the address space are never within.
+pub fn within(addr: u64, len: usize, start: u64, size: usize) -> bool {
+    match ( addr.checked_add( len as u64 ), start.checked_add( size as u64 ) ) {
+        ( Some(end), Some(limit) ) => addr >= start && end <= limit,
+        _ => false,
+    }
+}
+
+/// How a system call accesses the memory a process passes to it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// Returns the start and size of the code and read-only data of the kernel
+/// image. Processes execute the code and may pass pointers to the read-only
+/// data, such as string literals, but never to the kernel's data or bss.
+#[cfg(not(test))]
+fn readonly() -> (u64, usize) {
+    let start = unsafe { &_start as *const u8 as u64 };
+    let end = unsafe { &_erodata as *const u8 as u64 };
+    ( start, ( end - start ) as usize )
+}
+
+/// Stands in for the code and read-only data of the kernel image in tests.
+#[cfg(test)]
+pub static RODATA: [u8; 64] = [0; 64];
+
+#[cfg(test)]
+fn readonly() -> (u64, usize) {
+    ( RODATA.as_ptr() as u64, RODATA.len() )
+}
+
+impl Process {
+    /// Returns `true` if the process may access the `len` bytes at `addr` as
+    /// `access`: they lie within its stack or a region it maps, or they are
+    /// read from the code or read-only data of the kernel image.
+    pub fn owns(&self, addr: u64, len: usize, access: Access) -> bool {
+        let ( ro_start, ro_size ) = readonly();
+        within( addr, len, self.stack.bottom().as_u64(), self.stack.size() )
+            || self.regions.iter().any( |r| within( addr, len, r.start().as_u64(), r.size() ) )
+            || ( access == Access::Read && within( addr, len, ro_start, ro_size ) )
+    }
+}
+
+/// A pointer to a `T` in the memory of a process, passed to a system call.
+/// It is only dereferenced by `read` and `write`, once checked against the
+/// memory of the process.
+#[derive(Debug)]
+pub struct UserPtr<T> {
+    addr: u64,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Copy> UserPtr<T> {
+    /// Returns the user pointer `addr`.
+    pub fn new(addr: u64) -> UserPtr<T> {
+        UserPtr { addr, _marker: PhantomData }
+    }
+
+    /// Returns the pointer as a kernel pointer if it is aligned for `T` and
+    /// `p` may access the `T` it points to as `access`.
+    pub fn check(&self, p: &Process, access: Access) -> Result<*mut T, Fault> {
+        if self.addr % mem::align_of::<T>() as u64 != 0 || !p.owns( self.addr, mem::size_of::<T>(), access ) {
+            return Err( Fault )
+        }
+        Ok( self.addr as *mut T )
+    }
+
+    /// Copies the `T` at the pointer out of the memory of `p`.
+    pub fn read(&self, p: &Process) -> Result<T, Fault> {
+        self.check( p, Access::Read ).map( |x| unsafe { ptr::read( x ) } )
+    }
+
+    /// Copies `value` to the pointer in the memory of `p`.
+    pub fn write(&self, p: &Process, value: T) -> Result<(), Fault> {
+        self.check( p, Access::Write ).map( |x| unsafe { ptr::write( x, value ) } )
+    }
+}
+
+/// A buffer of bytes in the memory of a process, passed to a system call as
+/// an address and a length.
+#[derive(Debug, Copy, Clone)]
+pub struct UserSlice {
+    addr: u64,
+    len: usize,
+}
+
+impl UserSlice {
+    /// Returns the user buffer of `len` bytes at `addr`.
+    pub fn new(addr: u64, len: u64) -> UserSlice {
+        UserSlice { addr, len: len as usize }
+    }
+
+    /// Returns the length of the buffer in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Fails if `p` may not access the buffer as `access`.
+    pub fn check(&self, p: &Process, access: Access) -> Result<(), Fault> {
+        if p.owns( self.addr, self.len, access ) { Ok(()) } else { Err( Fault ) }
+    }
+
+    /// Copies the first `dst.len()` bytes of the buffer out of the memory of
+    /// `p`. `dst` must not be longer than the buffer.
+    pub fn copy_from_user(&self, p: &Process, dst: &mut [u8]) -> Result<(), Fault> {
+        assert!( dst.len() <= self.len );
+        self.check( p, Access::Read )?;
+        unsafe { ptr::copy_nonoverlapping( self.addr as *const u8, dst.as_mut_ptr(), dst.len() ); }
+        Ok(())
+    }
+
+    /// Copies `src` to the start of the buffer in the memory of `p`. `src`
+    /// must not be longer than the buffer.
+    pub fn copy_to_user(&self, p: &Process, src: &[u8]) -> Result<(), Fault> {
+        assert!( src.len() <= self.len );
+        self.check( p, Access::Write )?;
+        unsafe { ptr::copy_nonoverlapping( src.as_ptr(), self.addr as *mut u8, src.len() ); }
+        Ok(())
+    }
+
+    /// Copies the whole buffer out of the memory of `p` into a new vector.
+    /// The buffer is checked before any memory is allocated for it.
+    pub fn to_vec(&self, p: &Process) -> Result<Vec<u8>, Fault> {
+        self.check( p, Access::Read )?;
+        let mut v = vec![0; self.len];
+        self.copy_from_user( p, &mut v )?;
+        Ok( v )
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use mutex::IrqMutex;
+use process::Id;
+use sync::{Sched, KernelSched, Waiter};
+use traps::TrapFrame;
+
+/// A queue of processes blocked until some event occurs.
+///
+/// A process calling `wait` is removed from the scheduler's ready queue and
+/// parked in the scheduler's blocked set until another process, a driver, or
+/// an IRQ handler calls `wake_one` or `wake_all` on the same queue. Unlike
+/// `State::Waiting`, a blocked process is never polled by the scheduler.
+///
+/// The queue is allocated on first use so that it can be built in `const`
+/// contexts.
+#[derive(Debug)]
+pub struct WaitQueue<S = KernelSched> {
+    waiters: IrqMutex<Option<VecDeque<Waiter>>>,
+    _sched: PhantomData<S>,
+}
+
+impl<S> WaitQueue<S> {
+    /// Returns a new, empty wait queue.
+    pub const fn new() -> WaitQueue<S> {
+        WaitQueue {
+            waiters: IrqMutex::new(None),
+            _sched: PhantomData,
+        }
+    }
+
+    /// Returns `true` if no process is waiting on this queue.
+    pub fn is_empty(&self) -> bool {
+        match *self.waiters.lock() {
+            Some(ref q) => q.is_empty(),
+            None => true,
+        }
+    }
+}
+
+impl<S: Sched> WaitQueue<S> {
+    /// Blocks the process currently executing in `tf` on this queue and
+    /// context switches `tf` to the next ready process.
+    ///
+    /// The blocked process resumes at the point `tf` was saved once it is
+    /// woken up and scheduled again. The queue stays locked until the process
+    /// is in the blocked set, so a wake up cannot be missed.
+    pub fn wait(&self, tf: &mut TrapFrame) {
+        let mut waiters = self.waiters.lock();
+        let w = S::block( tf );
+        waiters.get_or_insert_with(VecDeque::new).push_back( w );
+    }
+
+    /// Wakes up the process that has been waiting the longest, moving it back
+    /// into the ready queue. Returns the ID of the woken process, if any.
+    pub fn wake_one(&self) -> Option<Id> {
+        let mut waiters = self.waiters.lock();
+        loop {
+            let w = waiters.as_mut()?.pop_front()?;
+            //skip processes that already left this wait
+            if S::unblock( w ) {
+                return Some( w.id )
+            }
+        }
+    }
+
+    /// Wakes up every process waiting on this queue. Returns the number of
+    /// processes that were woken.
+    pub fn wake_all(&self) -> usize {
+        let mut waiters = self.waiters.lock();
+        let mut woken = 0;
+        while let Some(w) = waiters.as_mut().and_then( |q| q.pop_front() ) {
+            if S::unblock( w ) {
+                woken += 1;
+            }
+        }
+        woken
+    }
+}
@@ -6,9 +6,16 @@ use fs;
 use fat32::traits;
 use cmds;
 use fs::FileSystem;
+use mutex::IrqMutex;
+use process::Id;
+use kthread;
 
 use FILE_SYSTEM;
 
+/// The process interrupted with `SIGINT` when Ctrl-C is typed, set with the
+/// `fg` command.
+pub static FOREGROUND: IrqMutex<Option<Id>> = IrqMutex::new(None);
+
 /// Error type for `Command` parse failures.
 #[derive(Debug)]
 enum Error {
@@ -80,6 +87,24 @@ fn flush_to_cmd<'b>( fs: & FileSystem,
                 "sleep" => {
                     < cmds::CmdSleep as cmds::ShellCmd >::execute( fs, fs_path, x.path(), &x.args.as_slice()[1..] );      
                 },
+                "ps" => {
+                    < cmds::CmdPs as cmds::ShellCmd >::execute( fs, fs_path, x.path(), &x.args.as_slice()[1..] );
+                },
+                "fg" => {
+                    < cmds::CmdFg as cmds::ShellCmd >::execute( fs, fs_path, x.path(), &x.args.as_slice()[1..] );
+                },
+                "affinity" => {
+                    < cmds::CmdAffinity as cmds::ShellCmd >::execute( fs, fs_path, x.path(), &x.args.as_slice()[1..] );
+                },
+                "strace" => {
+                    < cmds::CmdStrace as cmds::ShellCmd >::execute( fs, fs_path, x.path(), &x.args.as_slice()[1..] );
+                },
+                "date" => {
+                    < cmds::CmdDate as cmds::ShellCmd >::execute( fs, fs_path, x.path(), &x.args.as_slice()[1..] );
+                },
+                "run" => {
+                    < cmds::CmdRun as cmds::ShellCmd >::execute( fs, fs_path, x.path(), &x.args.as_slice()[1..] );
+                },
                 "exit" => {
                     kprintln!("exiting..");
                     return true
@@ -124,7 +149,6 @@ fn shift_left( cursor: & mut usize, end: & mut usize, buf: & mut [u8] ) {
 /// Starts a shell using `prefix` as the prefix for each line.
 pub fn shell(prefix: &str, fs: & FileSystem ) {
 
-    use std::io::Read;
     use std::io::Write;
     use std::str;
     use std::fmt;
@@ -153,10 +177,8 @@ pub fn shell(prefix: &str, fs: & FileSystem ) {
     
     loop {
         
-        let bytes_len = {
-            let mut c = CONSOLE.lock();
-            c.read( & mut buf[idx_end..512] ).expect("num of bytes read")
-        };
+        //wait for input in the kernel, letting other processes run meanwhile
+        let bytes_len = kthread::read( 0, & mut buf[idx_end..512] ).expect("num of bytes read");
 
         let offset = idx_end;
         for i in offset..offset + bytes_len {
@@ -181,6 +203,24 @@ pub fn shell(prefix: &str, fs: & FileSystem ) {
                 idx_cursor += 1;
                 idx_end += 1;
                 kprint!( "{}", s ); //print character
+            } else if buf[i] == 0x03 { //ctrl-c, interrupt the foreground process
+                use process::signal::{self, SIGINT};
+
+                kprintln!( "^C" );
+                let fg = *FOREGROUND.lock();
+                if let Some(id) = fg {
+                    if !signal::kill( id, SIGINT ) {
+                        kprintln!( "process {} is gone", id );
+                        *FOREGROUND.lock() = None;
+                    }
+                }
+                kprint!( "{}", prefix );
+                for i in 0..idx_end {
+                    buf[i] = 0x00;
+                }
+                idx_cursor = 0;
+                idx_end = 0;
+                break;
             } else if buf[i] == 0x7F || buf[i] == 0x08 { //delete or backspace
                 buf[i] == 0x20;
                 if idx_cursor != 0 {
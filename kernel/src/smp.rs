@@ -0,0 +1,116 @@
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use aarch64;
+use process::Stack;
+
+/// Number of cores on the BCM2837.
+pub const NCORES: usize = 4;
+
+/// Bit mask of every core.
+pub const ALL_CORES: usize = ( 1 << NCORES ) - 1;
+
+/// Size of the EL1 stack of each secondary core.
+const KERNEL_STACK_SIZE: usize = 1 << 16;
+
+/// Address of the spin table mailbox of core 0. The firmware parks every
+/// secondary core `n` in a `wfe` loop polling the 64-bit mailbox at
+/// `SPIN_TABLE_BASE + 8 * n` for an address to jump to.
+const SPIN_TABLE_BASE: usize = 0xd8;
+
+/// How long to wait for a secondary core to come online.
+const START_TIMEOUT_US: u64 = 100_000;
+
+/// EL1 stack pointer of each secondary core, read by `init.S` before the core
+/// switches to EL1. A core waits until its entry is non-zero.
+#[no_mangle]
+pub static mut CORE_STACKS: [u64; NCORES] = [0; NCORES];
+
+/// Bit mask of the cores that are running the scheduler.
+static ONLINE: AtomicUsize = AtomicUsize::new(1);
+
+extern "C" {
+    static _start: u8;
+}
+
+/// Returns the core currently executing.
+#[inline(always)]
+pub fn core() -> usize {
+    unsafe { aarch64::affinity() }
+}
+
+/// Returns the bit mask of the cores that are online.
+pub fn online() -> usize {
+    ONLINE.load( Ordering::Acquire )
+}
+
+/// Returns `true` if `core` is online.
+pub fn is_online(core: usize) -> bool {
+    core < NCORES && online() & ( 1 << core ) != 0
+}
+
+/// Sets the bit mask of the online cores, for tests of scheduling decisions.
+#[cfg(test)]
+pub fn set_online_mask(mask: usize) {
+    ONLINE.store( mask, Ordering::Release );
+}
+
+/// Marks the calling core as online.
+pub fn set_online() {
+    ONLINE.fetch_or( 1 << core(), Ordering::Release );
+}
+
+/// Returns the top of the EL1 stack of `core`.
+pub fn stack_top(core: usize) -> u64 {
+    match core {
+        0 => unsafe { &_start as *const u8 as u64 },
+        n => unsafe { CORE_STACKS[n] },
+    }
+}
+
+/// Releases cores 1 to 3 from the firmware spin table. Each core gets its own
+/// EL1 stack, sets up its own exception vectors in `init.S` and enters
+/// `kmain_secondary`. Returns the bit mask of the online cores.
+///
+/// Waits up to `START_TIMEOUT_US` for each core to come online.
+///
+/// Must not be called before the MMU and D-cache are enabled: the exclusive
+/// loads and stores `Mutex` relies on don't synchronize between cores
+/// otherwise.
+#[allow(dead_code)]
+pub fn start_secondary_cores() -> usize {
+    use console::kprintln;
+    use pi::timer;
+
+    for n in 1..NCORES {
+        let stack = match Stack::new( KERNEL_STACK_SIZE ) {
+            Some(x) => x,
+            None => {
+                kprintln!( "core {}: failed to allocate a kernel stack", n );
+                continue
+            },
+        };
+
+        unsafe {
+            CORE_STACKS[n] = stack.top().as_u64();
+            mem::forget( stack ); //lives as long as the core
+
+            let mailbox = ( SPIN_TABLE_BASE + 8 * n ) as *mut u64;
+            ptr::write_volatile( mailbox, &_start as *const u8 as u64 );
+            asm!("dsb sy
+                  sev" :::: "volatile");
+        }
+
+        let t_start = timer::current_time();
+        while !is_online( n ) && timer::current_time() - t_start < START_TIMEOUT_US {}
+
+        if is_online( n ) {
+            kprintln!( "core {}: online", n );
+        } else {
+            kprintln!( "core {}: did not come online", n );
+        }
+    }
+
+    online()
+}
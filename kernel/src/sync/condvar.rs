@@ -0,0 +1,50 @@
+use mutex::IrqMutexGuard;
+use process::WaitQueue;
+use sync::{Sched, KernelSched};
+use traps::TrapFrame;
+
+/// A condition variable that puts processes to sleep instead of spinning.
+///
+/// A woken process resumes where `tf` pointed to when it called `wait`; it
+/// does not return from `wait` holding the lock. Callers that need to check
+/// their condition again, such as a system call reading from an empty buffer,
+/// rewind `tf` before waiting so that the whole operation is retried.
+#[derive(Debug)]
+pub struct Condvar<S = KernelSched> {
+    waiters: WaitQueue<S>,
+}
+
+impl<S> Condvar<S> {
+    /// Returns a condition variable with no waiters.
+    pub const fn new() -> Condvar<S> {
+        Condvar {
+            waiters: WaitQueue::new(),
+        }
+    }
+}
+
+impl<S: Sched> Condvar<S> {
+    /// Blocks the process executing in `tf` until notified. `guard` protects
+    /// the condition being waited for and is released only once the process
+    /// is blocked, so a notification from a process that takes the same lock
+    /// cannot be missed.
+    pub fn wait<T>(&self, guard: IrqMutexGuard<T>, tf: &mut TrapFrame) {
+        self.waiters.wait( tf );
+        drop( guard );
+    }
+
+    /// Wakes up the longest waiting process. Returns `true` if one was woken.
+    pub fn notify_one(&self) -> bool {
+        self.waiters.wake_one().is_some()
+    }
+
+    /// Wakes up every waiting process. Returns the number of processes woken.
+    pub fn notify_all(&self) -> usize {
+        self.waiters.wake_all()
+    }
+
+    /// Returns `true` if a process is waiting.
+    pub fn has_waiters(&self) -> bool {
+        !self.waiters.is_empty()
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::marker::PhantomData;
+use std::ptr;
+
+use mutex::IrqMutex;
+use sync::{Sched, KernelSched, Waiter};
+use traps::TrapFrame;
+
+/// Processes blocked on user-space words, keyed by the address of the word.
+///
+/// This is the kernel half of a futex: user programs keep their lock state in
+/// a 32-bit word of their own memory and update it with atomic instructions.
+/// They only enter the kernel to sleep while the word has a given value, or to
+/// wake up the processes sleeping on it. A queue exists only while a process
+/// waits on its address.
+#[derive(Debug)]
+pub struct Futexes<S = KernelSched> {
+    queues: IrqMutex<Option<BTreeMap<usize, VecDeque<Waiter>>>>,
+    _sched: PhantomData<S>,
+}
+
+impl<S> Futexes<S> {
+    /// Returns a table with no waiters.
+    pub const fn new() -> Futexes<S> {
+        Futexes {
+            queues: IrqMutex::new(None),
+            _sched: PhantomData,
+        }
+    }
+}
+
+impl<S: Sched> Futexes<S> {
+    /// Blocks the process executing in `tf` on `addr` if the word at `addr`
+    /// still holds `expected`. Returns `false` without blocking otherwise.
+    ///
+    /// The word is read while holding the table lock, so a `wake` issued after
+    /// the word was changed cannot be missed.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be valid for reads and aligned to 4 bytes.
+    pub unsafe fn wait(&self, addr: *const u32, expected: u32, tf: &mut TrapFrame) -> bool {
+        let mut queues = self.queues.lock();
+        if ptr::read_volatile( addr ) != expected {
+            return false
+        }
+
+        let w = S::block( tf );
+        queues.get_or_insert_with(BTreeMap::new)
+            .entry( addr as usize )
+            .or_insert_with(VecDeque::new)
+            .push_back( w );
+        true
+    }
+
+    /// Wakes up at most `n` processes waiting on `addr`, in the order they
+    /// started waiting. Returns the number of processes woken.
+    pub fn wake(&self, addr: *const u32, n: usize) -> usize {
+        let mut guard = self.queues.lock();
+        let queues = match guard.as_mut() {
+            Some(x) => x,
+            None => return 0,
+        };
+
+        let mut woken = 0;
+        let empty = match queues.get_mut( &( addr as usize ) ) {
+            Some(queue) => {
+                while woken < n {
+                    match queue.pop_front() {
+                        Some(w) => if S::unblock( w ) { woken += 1; },
+                        None => break,
+                    }
+                }
+                queue.is_empty()
+            },
+            None => false,
+        };
+
+        if empty {
+            queues.remove( &( addr as usize ) );
+        }
+        woken
+    }
+
+    /// Returns the number of processes waiting on `addr`.
+    pub fn waiters(&self, addr: *const u32) -> usize {
+        match *self.queues.lock() {
+            Some(ref queues) => queues.get( &( addr as usize ) ).map_or(0, |q| q.len()),
+            None => 0,
+        }
+    }
+}
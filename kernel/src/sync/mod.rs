@@ -0,0 +1,83 @@
+mod semaphore;
+mod condvar;
+mod rwlock;
+mod futex;
+
+#[cfg(test)]
+pub mod sim;
+#[cfg(test)]
+mod tests;
+
+use pi::timer;
+use process::{Id, Process, State};
+use traps::TrapFrame;
+use SCHEDULER;
+
+pub use self::semaphore::Semaphore;
+pub use self::condvar::Condvar;
+pub use self::rwlock::RwLock;
+pub use self::futex::Futexes;
+
+/// A process blocked in a particular wait.
+///
+/// A process interrupted by a signal leaves its wait without being removed
+/// from the queue it waits in. The token tells a later wait apart, so waking
+/// up the stale entry neither wakes the process from another wait nor a
+/// process that reuses its ID, and the wake up goes to the next entry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Waiter {
+    pub id: Id,
+    pub token: u64,
+}
+
+/// The scheduler operations the sleeping primitives and system calls rely on.
+///
+/// The primitives are called from kernel context with the trap frame of the
+/// calling process. Blocking switches `tf` to another process; the blocked
+/// process resumes where `tf` pointed to once it is woken up and scheduled.
+pub trait Sched {
+    /// Blocks the process executing in `tf` and switches `tf` to the next
+    /// ready process. Returns the waiter to pass to `unblock`.
+    fn block(tf: &mut TrapFrame) -> Waiter;
+
+    /// Moves the process of `w` back to a ready queue. Returns `false` if it
+    /// is no longer blocked in the wait `w` was returned for.
+    fn unblock(w: Waiter) -> bool;
+
+    /// Calls `f` on the process with ID `id`. Returns `None` if there is no
+    /// such process.
+    fn with_process<R, F: FnOnce(&mut Process) -> R>(id: Id, f: F) -> Option<R>;
+
+    /// Switches the process executing in `tf` to `state` and `tf` to the next
+    /// ready process. A `Waiting` process is polled until it is ready again.
+    fn switch(state: State, tf: &mut TrapFrame);
+
+    /// Returns the time waits are measured against, in microseconds.
+    fn now() -> u64;
+}
+
+/// The kernel's global scheduler.
+#[derive(Debug)]
+pub struct KernelSched;
+
+impl Sched for KernelSched {
+    fn block(tf: &mut TrapFrame) -> Waiter {
+        SCHEDULER.block( tf )
+    }
+
+    fn unblock(w: Waiter) -> bool {
+        SCHEDULER.wake( w )
+    }
+
+    fn with_process<R, F: FnOnce(&mut Process) -> R>(id: Id, f: F) -> Option<R> {
+        SCHEDULER.with_process( id, f )
+    }
+
+    fn switch(state: State, tf: &mut TrapFrame) {
+        let _ = SCHEDULER.switch( state, tf );
+    }
+
+    fn now() -> u64 {
+        timer::current_time()
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use mutex::IrqMutex;
+use sync::{Sched, KernelSched, Waiter};
+use traps::TrapFrame;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Access {
+    Read,
+    Write,
+}
+
+#[derive(Debug)]
+struct Inner {
+    readers: usize,
+    writer: bool,
+    waiters: Option<VecDeque<(Waiter, Access)>>,
+}
+
+impl Inner {
+    fn has_waiters(&self) -> bool {
+        match self.waiters {
+            Some(ref q) => !q.is_empty(),
+            None => false,
+        }
+    }
+
+    fn push(&mut self, w: Waiter, access: Access) {
+        self.waiters.get_or_insert_with(VecDeque::new).push_back((w, access));
+    }
+}
+
+/// A reader-writer lock that puts processes to sleep instead of spinning.
+///
+/// Any number of readers or a single writer may hold the lock. Waiting
+/// processes are served in FIFO order and a new reader queues behind a
+/// waiting writer, so writers are not starved. Like `Semaphore`, the lock is
+/// handed off on release: a process that blocked in `read` or `write` holds
+/// the lock when it resumes and must release it with `release_read` or
+/// `release_write`.
+#[derive(Debug)]
+pub struct RwLock<S = KernelSched> {
+    inner: IrqMutex<Inner>,
+    _sched: PhantomData<S>,
+}
+
+impl<S> RwLock<S> {
+    /// Returns an unlocked reader-writer lock.
+    pub const fn new() -> RwLock<S> {
+        RwLock {
+            inner: IrqMutex::new(Inner { readers: 0, writer: false, waiters: None }),
+            _sched: PhantomData,
+        }
+    }
+}
+
+impl<S: Sched> RwLock<S> {
+    /// Acquires shared access, blocking the process executing in `tf` while a
+    /// writer holds or waits for the lock. Returns `true` if the lock was
+    /// acquired without blocking.
+    pub fn read(&self, tf: &mut TrapFrame) -> bool {
+        let mut inner = self.inner.lock();
+        if !inner.writer && !inner.has_waiters() {
+            inner.readers += 1;
+            return true
+        }
+
+        let w = S::block( tf );
+        inner.push( w, Access::Read );
+        false
+    }
+
+    /// Acquires exclusive access, blocking the process executing in `tf`
+    /// while the lock is held. Returns `true` if the lock was acquired
+    /// without blocking.
+    pub fn write(&self, tf: &mut TrapFrame) -> bool {
+        let mut inner = self.inner.lock();
+        if !inner.writer && inner.readers == 0 && !inner.has_waiters() {
+            inner.writer = true;
+            return true
+        }
+
+        let w = S::block( tf );
+        inner.push( w, Access::Write );
+        false
+    }
+
+    /// Acquires shared access if it is available without blocking.
+    pub fn try_read(&self) -> bool {
+        let mut inner = self.inner.lock();
+        if !inner.writer && !inner.has_waiters() {
+            inner.readers += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Acquires exclusive access if it is available without blocking.
+    pub fn try_write(&self) -> bool {
+        let mut inner = self.inner.lock();
+        if !inner.writer && inner.readers == 0 && !inner.has_waiters() {
+            inner.writer = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Releases shared access. The last reader hands the lock to the next
+    /// waiting writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is not held for reading.
+    pub fn release_read(&self) {
+        let mut inner = self.inner.lock();
+        assert!( inner.readers > 0, "release_read on a lock not held for reading" );
+        inner.readers -= 1;
+        if inner.readers == 0 {
+            Self::hand_off( &mut inner );
+        }
+    }
+
+    /// Releases exclusive access, handing the lock to the next waiting
+    /// writer, or to every reader queued before the next waiting writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is not held for writing.
+    pub fn release_write(&self) {
+        let mut inner = self.inner.lock();
+        assert!( inner.writer, "release_write on a lock not held for writing" );
+        inner.writer = false;
+        Self::hand_off( &mut inner );
+    }
+
+    /// Grants the free lock to the processes at the front of the queue.
+    fn hand_off(inner: &mut Inner) {
+        let queue = match inner.waiters {
+            Some(ref mut q) => q,
+            None => return,
+        };
+
+        while let Some(&(w, access)) = queue.front() {
+            match access {
+                Access::Write if inner.readers > 0 => break,
+                Access::Write => {
+                    queue.pop_front();
+                    if S::unblock( w ) {
+                        inner.writer = true;
+                        break
+                    }
+                },
+                Access::Read => {
+                    queue.pop_front();
+                    if S::unblock( w ) {
+                        inner.readers += 1;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Returns the number of readers holding the lock.
+    pub fn readers(&self) -> usize {
+        self.inner.lock().readers
+    }
+
+    /// Returns `true` if a writer holds the lock.
+    pub fn is_write_locked(&self) -> bool {
+        self.inner.lock().writer
+    }
+}
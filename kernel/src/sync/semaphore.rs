@@ -0,0 +1,66 @@
+use mutex::IrqMutex;
+use process::WaitQueue;
+use sync::{Sched, KernelSched};
+use traps::TrapFrame;
+
+/// A counting semaphore that puts processes to sleep instead of spinning.
+///
+/// Units are handed off directly: `release` gives its unit to the process
+/// that has been waiting the longest and wakes it up, so a process that
+/// blocked in `acquire` owns a unit when it resumes.
+#[derive(Debug)]
+pub struct Semaphore<S = KernelSched> {
+    count: IrqMutex<usize>,
+    waiters: WaitQueue<S>,
+}
+
+impl<S> Semaphore<S> {
+    /// Returns a semaphore with `count` available units.
+    pub const fn new(count: usize) -> Semaphore<S> {
+        Semaphore {
+            count: IrqMutex::new(count),
+            waiters: WaitQueue::new(),
+        }
+    }
+}
+
+impl<S: Sched> Semaphore<S> {
+    /// Takes a unit if one is available. Otherwise blocks the process
+    /// executing in `tf` until a unit is handed to it by `release`.
+    ///
+    /// Returns `true` if a unit was taken without blocking.
+    pub fn acquire(&self, tf: &mut TrapFrame) -> bool {
+        let mut count = self.count.lock();
+        if *count > 0 {
+            *count -= 1;
+            return true
+        }
+
+        self.waiters.wait( tf );
+        false
+    }
+
+    /// Takes a unit if one is available. Never blocks.
+    pub fn try_acquire(&self) -> bool {
+        let mut count = self.count.lock();
+        if *count > 0 {
+            *count -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns a unit, handing it to the longest waiting process if any.
+    pub fn release(&self) {
+        let mut count = self.count.lock();
+        if self.waiters.wake_one().is_none() {
+            *count += 1;
+        }
+    }
+
+    /// Returns the number of available units.
+    pub fn count(&self) -> usize {
+        *self.count.lock()
+    }
+}
@@ -0,0 +1,178 @@
+//! A simulated single-core scheduler for host tests of code that blocks
+//! processes.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::mem;
+
+use process::{Id, Process, State};
+use sync::{Sched, Waiter};
+use traps::TrapFrame;
+
+/// A single-core scheduler: blocking moves the current process to the
+/// blocked list and switches `tf` to the front of the ready queue.
+#[derive(Default)]
+pub struct Sim {
+    pub ready: VecDeque<Id>,
+    pub blocked: Vec<Waiter>,
+    /// The token of the last wait.
+    pub token: u64,
+    /// The processes added with `spawn`.
+    pub processes: Vec<Process>,
+    /// The current time, in microseconds.
+    pub now: u64,
+}
+
+thread_local! {
+    pub static SIM: RefCell<Sim> = RefCell::new(Sim::default());
+}
+
+pub struct SimSched;
+
+impl Sched for SimSched {
+    fn block(tf: &mut TrapFrame) -> Waiter {
+        SIM.with(|s| {
+            let mut s = s.borrow_mut();
+            s.token += 1;
+            let w = Waiter { id: tf.TPIDR, token: s.token };
+            s.blocked.push(w);
+            tf.TPIDR = s.ready.pop_front().unwrap_or(0);
+            w
+        })
+    }
+
+    fn unblock(w: Waiter) -> bool {
+        SIM.with(|s| {
+            let mut s = s.borrow_mut();
+            match s.blocked.iter().position(|x| *x == w) {
+                Some(i) => {
+                    s.blocked.remove(i);
+                    s.ready.push_back(w.id);
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+
+    fn with_process<R, F: FnOnce(&mut Process) -> R>(id: Id, f: F) -> Option<R> {
+        SIM.with(|s| {
+            let mut s = s.borrow_mut();
+            s.processes.iter_mut().find(|p| p.trap_frame.TPIDR == id).map(f)
+        })
+    }
+
+    fn switch(state: State, tf: &mut TrapFrame) {
+        SIM.with(|s| {
+            let mut s = s.borrow_mut();
+            match state {
+                State::Ready => s.ready.push_back(tf.TPIDR),
+                State::Dead => s.processes.retain(|p| p.trap_frame.TPIDR != tf.TPIDR),
+                _ => {}
+            }
+            if let Some(p) = s.processes.iter_mut().find(|p| p.trap_frame.TPIDR == tf.TPIDR) {
+                *p.trap_frame = *tf;
+                p.state = state;
+            }
+            tf.TPIDR = s.ready.pop_front().unwrap_or(0);
+        })
+    }
+
+    fn now() -> u64 {
+        SIM.with(|s| s.borrow().now)
+    }
+}
+
+/// Adds a process with ID `id` for system calls to run against.
+pub fn spawn(id: Id) {
+    let mut p = Process::new(4096).expect("stack");
+    p.trap_frame.TPIDR = id;
+    SIM.with(|s| s.borrow_mut().processes.push(p))
+}
+
+/// Advances the current time by `us` microseconds.
+pub fn advance(us: u64) {
+    SIM.with(|s| s.borrow_mut().now += us)
+}
+
+/// Polls every waiting process, like the scheduler does on each time slice,
+/// and makes the ones whose event has arrived ready, in order.
+pub fn poll() {
+    //polled outside of the borrow, since the poll functions read the time
+    let mut processes = SIM.with(|s| mem::replace(&mut s.borrow_mut().processes, Vec::new()));
+    let mut woken = Vec::new();
+    for p in processes.iter_mut() {
+        if let State::Waiting(_) = p.state {
+            if p.is_ready() {
+                p.state = State::Ready;
+                woken.push(p.trap_frame.TPIDR);
+            }
+        }
+    }
+    SIM.with(|s| {
+        let mut s = s.borrow_mut();
+        s.processes = processes;
+        s.ready.extend(woken);
+    })
+}
+
+/// Returns `true` if process `id` is waiting for an event.
+pub fn is_waiting(id: Id) -> bool {
+    SimSched::with_process(id, |p| match p.state {
+        State::Waiting(_) => true,
+        _ => false,
+    }).unwrap_or(false)
+}
+
+/// Returns a trap frame for process `id`.
+pub fn tf(id: Id) -> TrapFrame {
+    let mut tf = TrapFrame::default();
+    tf.TPIDR = id;
+    tf
+}
+
+/// Makes processes `ids` ready, in order.
+pub fn ready(ids: &[Id]) {
+    SIM.with(|s| s.borrow_mut().ready.extend(ids.iter().cloned()))
+}
+
+pub fn is_blocked(id: Id) -> bool {
+    SIM.with(|s| s.borrow().blocked.iter().any(|x| x.id == id))
+}
+
+pub fn ready_queue() -> Vec<Id> {
+    SIM.with(|s| s.borrow().ready.iter().cloned().collect())
+}
+
+/// Simulates process `id` exiting or being removed while blocked.
+pub fn kill(id: Id) {
+    SIM.with(|s| s.borrow_mut().blocked.retain(|x| x.id != id))
+}
+
+/// Simulates a signal interrupting process `id` while blocked: it leaves its
+/// wait and is made ready, leaving its entry in the wait queue behind.
+pub fn interrupt(id: Id) {
+    SIM.with(|s| {
+        let mut s = s.borrow_mut();
+        if let Some(i) = s.blocked.iter().position(|x| x.id == id) {
+            s.blocked.remove(i);
+            s.ready.push_back(id);
+        }
+    })
+}
+
+/// Moves the process executing in `tf` to the back of the ready queue and
+/// switches `tf` to the front of the queue.
+pub fn preempt(tf: &mut TrapFrame) {
+    SIM.with(|s| {
+        let mut s = s.borrow_mut();
+        s.ready.push_back(tf.TPIDR);
+        tf.TPIDR = s.ready.pop_front().unwrap();
+    })
+}
+
+/// Drops the process executing in `tf` and switches `tf` to the front of the
+/// ready queue, or to `0` if no process is ready.
+pub fn exit(tf: &mut TrapFrame) {
+    SIM.with(|s| tf.TPIDR = s.borrow_mut().ready.pop_front().unwrap_or(0))
+}
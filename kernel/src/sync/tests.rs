@@ -0,0 +1,332 @@
+mod wait_queue {
+    use process::WaitQueue;
+    use sync::sim::*;
+
+    #[test]
+    fn wake_one_in_fifo_order() {
+        let q: WaitQueue<SimSched> = WaitQueue::new();
+        assert!(q.is_empty());
+        assert_eq!(q.wake_one(), None);
+        ready(&[2, 3]);
+
+        let mut t = tf(1);
+        q.wait(&mut t);
+        q.wait(&mut t);
+        assert_eq!(t.TPIDR, 3);
+        assert!(!q.is_empty());
+
+        assert_eq!(q.wake_one(), Some(1));
+        assert_eq!(q.wake_one(), Some(2));
+        assert_eq!(q.wake_one(), None);
+        assert_eq!(ready_queue(), vec![1, 2]);
+    }
+
+    #[test]
+    fn wake_all_skips_dead_waiters() {
+        let q: WaitQueue<SimSched> = WaitQueue::new();
+        ready(&[2, 3, 4]);
+
+        let mut t = tf(1);
+        q.wait(&mut t);
+        q.wait(&mut t);
+        q.wait(&mut t);
+        kill(2);
+
+        assert_eq!(q.wake_all(), 2);
+        assert!(q.is_empty());
+        assert_eq!(ready_queue(), vec![1, 3]);
+    }
+
+    #[test]
+    fn interrupted_waiter_is_skipped() {
+        let q: WaitQueue<SimSched> = WaitQueue::new();
+        let other: WaitQueue<SimSched> = WaitQueue::new();
+        ready(&[2, 3]);
+
+        let mut t = tf(1);
+        q.wait(&mut t);
+        interrupt(1);
+        q.wait(&mut t);
+        preempt(&mut t);
+        assert_eq!(t.TPIDR, 1);
+        other.wait(&mut t);
+
+        //the entry 1 left behind neither wakes it from `other` nor takes the
+        //wake up from 2
+        assert_eq!(q.wake_one(), Some(2));
+        assert!(is_blocked(1));
+        assert!(q.is_empty());
+        assert_eq!(other.wake_one(), Some(1));
+    }
+}
+
+mod futex {
+    use sync::Futexes;
+    use sync::sim::*;
+
+    #[test]
+    fn wait_checks_the_word() {
+        let futexes: Futexes<SimSched> = Futexes::new();
+        let word = 1u32;
+        ready(&[2]);
+
+        let mut t = tf(1);
+        assert!(!unsafe { futexes.wait(&word, 0, &mut t) });
+        assert_eq!(t.TPIDR, 1);
+        assert_eq!(futexes.waiters(&word), 0);
+    }
+
+    #[test]
+    fn wake_skips_interrupted_waiters() {
+        let futexes: Futexes<SimSched> = Futexes::new();
+        let word = 0u32;
+        ready(&[2, 3]);
+
+        let mut t = tf(1);
+        assert!(unsafe { futexes.wait(&word, 0, &mut t) });
+        interrupt(1);
+        assert!(unsafe { futexes.wait(&word, 0, &mut t) });
+        assert_eq!(futexes.waiters(&word), 2);
+
+        assert_eq!(futexes.wake(&word, 1), 1);
+        assert!(!is_blocked(2));
+        assert_eq!(ready_queue(), vec![1, 2]);
+        assert_eq!(futexes.waiters(&word), 0);
+    }
+}
+
+mod semaphore {
+    use sync::Semaphore;
+    use sync::sim::*;
+
+    #[test]
+    fn acquire_without_blocking() {
+        let sem: Semaphore<SimSched> = Semaphore::new(2);
+        let mut t = tf(1);
+        assert!(sem.acquire(&mut t));
+        assert!(sem.acquire(&mut t));
+        assert_eq!(t.TPIDR, 1);
+        assert_eq!(sem.count(), 0);
+        assert!(!sem.try_acquire());
+    }
+
+    #[test]
+    fn blocks_when_exhausted() {
+        let sem: Semaphore<SimSched> = Semaphore::new(0);
+        ready(&[2]);
+
+        let mut t = tf(1);
+        assert!(!sem.acquire(&mut t));
+        assert!(is_blocked(1));
+        assert_eq!(t.TPIDR, 2); //switched to the next ready process
+    }
+
+    #[test]
+    fn release_hands_off_in_fifo_order() {
+        let sem: Semaphore<SimSched> = Semaphore::new(0);
+        ready(&[2, 3, 4]);
+
+        let mut t = tf(1);
+        assert!(!sem.acquire(&mut t));
+        assert!(!sem.acquire(&mut t));
+        assert!(!sem.acquire(&mut t));
+        assert_eq!(t.TPIDR, 4);
+        assert!(ready_queue().is_empty());
+
+        sem.release();
+        assert!(!is_blocked(1));
+        assert!(is_blocked(2));
+        assert_eq!(ready_queue(), vec![1]);
+        //the unit went to the woken process
+        assert_eq!(sem.count(), 0);
+
+        sem.release();
+        sem.release();
+        assert_eq!(ready_queue(), vec![1, 2, 3]);
+        assert_eq!(sem.count(), 0);
+
+        sem.release();
+        assert_eq!(sem.count(), 1);
+    }
+
+    #[test]
+    fn release_skips_dead_waiters() {
+        let sem: Semaphore<SimSched> = Semaphore::new(0);
+        ready(&[2, 3]);
+
+        let mut t = tf(1);
+        assert!(!sem.acquire(&mut t));
+        assert!(!sem.acquire(&mut t));
+        kill(1);
+
+        sem.release();
+        assert_eq!(ready_queue(), vec![2]);
+        assert_eq!(sem.count(), 0);
+    }
+}
+
+mod condvar {
+    use mutex::IrqMutex;
+    use sync::Condvar;
+    use sync::sim::*;
+
+    #[test]
+    fn notify_without_waiters() {
+        let cv: Condvar<SimSched> = Condvar::new();
+        assert!(!cv.has_waiters());
+        assert!(!cv.notify_one());
+        assert_eq!(cv.notify_all(), 0);
+    }
+
+    #[test]
+    fn wait_blocks_until_notified() {
+        let lock = IrqMutex::new(false);
+        let cv: Condvar<SimSched> = Condvar::new();
+        ready(&[2]);
+
+        let mut t = tf(1);
+        cv.wait(lock.lock(), &mut t);
+        assert!(is_blocked(1));
+        assert!(cv.has_waiters());
+        assert_eq!(t.TPIDR, 2);
+
+        *lock.lock() = true;
+        assert!(cv.notify_one());
+        assert!(!is_blocked(1));
+        assert!(!cv.has_waiters());
+        assert_eq!(ready_queue(), vec![1]);
+    }
+
+    #[test]
+    fn notify_one_wakes_in_fifo_order() {
+        let lock = IrqMutex::new(());
+        let cv: Condvar<SimSched> = Condvar::new();
+        ready(&[2, 3]);
+
+        let mut t = tf(1);
+        cv.wait(lock.lock(), &mut t);
+        cv.wait(lock.lock(), &mut t);
+
+        assert!(cv.notify_one());
+        assert_eq!(ready_queue(), vec![1]);
+        assert!(cv.notify_one());
+        assert_eq!(ready_queue(), vec![1, 2]);
+        assert!(!cv.notify_one());
+    }
+
+    #[test]
+    fn notify_all_wakes_every_waiter() {
+        let lock = IrqMutex::new(());
+        let cv: Condvar<SimSched> = Condvar::new();
+        ready(&[2, 3, 4]);
+
+        let mut t = tf(1);
+        cv.wait(lock.lock(), &mut t);
+        cv.wait(lock.lock(), &mut t);
+        cv.wait(lock.lock(), &mut t);
+        kill(2);
+
+        assert_eq!(cv.notify_all(), 2);
+        assert_eq!(ready_queue(), vec![1, 3]);
+        assert!(!cv.has_waiters());
+    }
+}
+
+mod rwlock {
+    use sync::RwLock;
+    use sync::sim::*;
+
+    #[test]
+    fn readers_share() {
+        let lock: RwLock<SimSched> = RwLock::new();
+        let mut t = tf(1);
+        assert!(lock.read(&mut t));
+        assert!(lock.read(&mut t));
+        assert!(lock.try_read());
+        assert_eq!(lock.readers(), 3);
+        assert!(!lock.try_write());
+
+        lock.release_read();
+        lock.release_read();
+        lock.release_read();
+        assert!(lock.try_write());
+        assert!(lock.is_write_locked());
+        assert!(!lock.try_read());
+    }
+
+    #[test]
+    fn writer_waits_for_readers() {
+        let lock: RwLock<SimSched> = RwLock::new();
+        ready(&[2]);
+
+        let mut t = tf(1);
+        assert!(lock.read(&mut t));
+        assert!(lock.read(&mut t));
+        assert!(!lock.write(&mut t));
+        assert!(is_blocked(1));
+        assert_eq!(t.TPIDR, 2);
+
+        lock.release_read();
+        assert!(is_blocked(1));
+        lock.release_read();
+        assert!(!is_blocked(1));
+        assert!(lock.is_write_locked());
+    }
+
+    #[test]
+    fn readers_queue_behind_waiting_writer() {
+        let lock: RwLock<SimSched> = RwLock::new();
+        ready(&[2, 3]);
+
+        let mut t = tf(1);
+        assert!(lock.read(&mut t));
+        assert!(!lock.write(&mut t)); //1 waits to write
+        assert_eq!(t.TPIDR, 2);
+        assert!(!lock.read(&mut t)); //2 queues behind the writer
+        assert!(!lock.try_read());
+        assert_eq!(lock.readers(), 1);
+
+        lock.release_read();
+        assert!(lock.is_write_locked());
+        assert_eq!(ready_queue(), vec![1]);
+        assert!(is_blocked(2));
+
+        lock.release_write();
+        assert_eq!(lock.readers(), 1);
+        assert_eq!(ready_queue(), vec![1, 2]);
+    }
+
+    #[test]
+    fn release_write_wakes_readers_up_to_next_writer() {
+        let lock: RwLock<SimSched> = RwLock::new();
+        ready(&[2, 3, 4, 5]);
+
+        let mut t = tf(1);
+        assert!(lock.write(&mut t));
+        assert!(!lock.read(&mut t)); //1
+        assert!(!lock.read(&mut t)); //2
+        assert!(!lock.write(&mut t)); //3
+        assert!(!lock.read(&mut t)); //4
+        assert_eq!(t.TPIDR, 5);
+
+        lock.release_write();
+        assert_eq!(lock.readers(), 2);
+        assert_eq!(ready_queue(), vec![1, 2]);
+
+        lock.release_read();
+        lock.release_read();
+        assert!(lock.is_write_locked());
+        assert_eq!(ready_queue(), vec![1, 2, 3]);
+
+        lock.release_write();
+        assert_eq!(lock.readers(), 1);
+        assert_eq!(ready_queue(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn release_unlocked() {
+        let lock: RwLock<SimSched> = RwLock::new();
+        lock.release_write();
+    }
+}
@@ -1,23 +1,86 @@
-use pi::interrupt::Interrupt;
+use std::mem;
 
+use pi::interrupt::{Controller, Interrupt, NIRQ};
+
+use mutex::IrqMutex;
 use traps::TrapFrame;
 
-pub fn handle_irq(interrupt: Interrupt, tf: &mut TrapFrame) {
-    //acknowledge and setup a new timer interrupt
-    match interrupt {
-        Interrupt::Timer1 => {
-            //read the compare register and modify for next interrupt
-            use pi::timer;
-            use process::TICK;
-            timer::Timer::new().tick_in( TICK );
-
-            //schedule processes
-            use SCHEDULER;
-            use process;
-            SCHEDULER.switch( process::State::Ready, tf );
-        },
-        _ => {},
+/// Type of an interrupt handler. It runs on core 0 with IRQs masked, and may
+/// switch `tf` to another process.
+pub type Handler = fn(&mut TrapFrame);
+
+/// The handler of each interrupt, by interrupt number.
+static HANDLERS: IrqMutex<[Option<Handler>; NIRQ]> = IrqMutex::new([None; NIRQ]);
+
+/// Installs `handler` for `int` and enables `int` at the interrupt
+/// controller. Returns the handler it replaces, if any.
+pub fn register(int: Interrupt, handler: Handler) -> Option<Handler> {
+    let old = mem::replace( &mut HANDLERS.lock()[ int.index() ], Some( handler ) );
+    Controller::new().enable( int );
+    old
+}
+
+/// Disables `int` at the interrupt controller and removes its handler, which
+/// is returned.
+pub fn unregister(int: Interrupt) -> Option<Handler> {
+    Controller::new().disable( int );
+    HANDLERS.lock()[ int.index() ].take()
+}
+
+/// Runs the handler of every pending interrupt that has one, GPU peripheral
+/// interrupts (including the GPIO banks) as well as the ARM interrupts of the
+/// basic pending register.
+///
+/// Stops once a handler has switched `tf` to another process: the remaining
+/// interrupts are still pending and are taken again in the context of the
+/// process switched in.
+pub fn dispatch(tf: &mut TrapFrame) {
+    let controller = Controller::new();
+    let id = tf.TPIDR;
+
+    for &int in Interrupt::ALL.iter() {
+        //copied out so that the handler runs without the lock held
+        let handler = HANDLERS.lock()[ int.index() ];
+        if let Some( handler ) = handler {
+            if controller.is_pending( int ) {
+                handler( tf );
+                if tf.TPIDR != id {
+                    break
+                }
+            }
+        }
     }
+
+    //enable interrupt again
+    tf.SPSR = tf.SPSR & !(1 << 7);
+}
+
+/// Handles the timer 1 interrupt of core 0: preempts the running process.
+pub fn handle_timer1(tf: &mut TrapFrame) {
+    use pi::timer;
+    use process::{self, TICK};
+    use SCHEDULER;
+
+    //read the compare register and modify for next interrupt
+    timer::Timer::new().tick_in( TICK );
+
+    //schedule processes; without a current process there is nothing to preempt
+    let _ = SCHEDULER.switch( process::State::Ready, tf );
+}
+
+/// Handles the generic timer interrupt of a secondary core.
+pub fn handle_local_timer(tf: &mut TrapFrame) {
+    use pi::local_timer;
+    use process::{self, TICK};
+    use SCHEDULER;
+
+    //writing the timer value clears the interrupt
+    local_timer::tick_in( TICK );
+
+    //schedule processes on this core; without a current process there is
+    //nothing to preempt
+    let _ = SCHEDULER.switch( process::State::Ready, tf );
+
     //enable interrupt again
     tf.SPSR = tf.SPSR & !(1 << 7);
 }
@@ -1,18 +1,22 @@
-mod irq;
+pub mod irq;
 mod trap_frame;
 mod syndrome;
 mod syscall;
+mod policy;
 
-use pi::interrupt::{Controller, Interrupt};
+#[cfg(test)]
+mod tests;
+
+use pi::local_timer;
 
 pub use self::trap_frame::TrapFrame;
 
-pub use self::syscall::syscall_sleep_ms;
     
 use console::kprintln;
 use self::syndrome::Syndrome;
-use self::irq::handle_irq;
+use self::irq::handle_local_timer;
 use self::syscall::handle_syscall;
+use self::policy::Action;
 
 #[repr(u16)]
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -43,58 +47,69 @@ pub struct Info {
 /// specifies the source and kind of exception that has occurred. The `esr` is
 /// the value of the exception syndrome register. Finally, `tf` is a pointer to
 /// the trap frame for the exception.
+///
+/// What is done with the exception is looked up in the policy table of
+/// `policy::action`.
 #[no_mangle]
 pub extern fn handle_exception(info: Info, esr: u32, tf: &mut TrapFrame) {
     use aarch64;
+    use backtrace;
+    use process;
+    use smp;
     use shell;
-    use fs::FileSystem;
     use FILE_SYSTEM;
+    use SCHEDULER;
 
-    match info.kind {
-        Kind::Synchronous => {
+    //ESR_ELx is only valid for synchronous exceptions
+    let syndrome = Syndrome::from( esr );
 
-            // for the cpase of synchronous instruction other than system calls such as brk,
-            // the CPU stores the address of instruction that generates the exception
-            // in ELR_ELx.
-            // Thus, to set address to the next instruction (32-bit wide) upon exception return, it is ELR_ELx + 4
-            tf.ELR += 4;
+    match policy::action( info.source, info.kind, syndrome ) {
+        Action::Syscall => {
+            //the preferred return address of `svc` is the next instruction
+            if let Syndrome::Svc(x) = syndrome {
+                handle_syscall( x, tf );
+            }
+        },
+        Action::Debug => {
+            kprintln!( "exception: {:?}", syndrome );
 
-            //ESR_ELx is valid if it's a synchronous exception
-            let syndrome = Syndrome::from( esr );
-            match syndrome {
-                Syndrome::Brk(x) => {
-                    
-                    kprintln!( "exception: brk: {:?}", x );
-                    
-                    shell::shell( "!brk>", & FILE_SYSTEM );
-                },
-                Syndrome::Svc(x) => {
-                    
-                    kprintln!( "exception: Svc: {:?}", x );
-                    
-                    handle_syscall( x, tf );
-                },
-                _ => {},
-            }       
+            shell::shell( "!brk>", & FILE_SYSTEM );
+
+            //ELR_ELx holds the address of the `brk` instruction itself, step
+            //over it (32-bit wide) on return
+            tf.ELR += 4;
         },
-        Kind::Irq => {
+        Action::Irq => {
 
             //forward interrupts such as ones coming from timers
-            
-            //kprintln!( "exception: irq: {:?}", info );
 
-            if Controller::new().is_pending( Interrupt::Timer1 ) {
-                handle_irq( Interrupt::Timer1, tf );
-            }
-            
-            if Controller::new().is_pending( Interrupt::Uart ) {
-                handle_irq( Interrupt::Uart, tf );
-            }
+            let core = smp::core();
 
-            if Controller::new().is_pending( Interrupt::Usb ) {
-                handle_irq( Interrupt::Usb, tf );
+            //peripheral interrupts are only routed to core 0
+            if core == 0 {
+                irq::dispatch( tf );
+            } else if local_timer::is_pending( core ) {
+                handle_local_timer( tf );
+            }
+        },
+        Action::Kill => {
+            //the faulting instruction would fault again if returned to
+            kprintln!( "process {}: {:?} {:?} exception: {:?} at {:#x}, killed",
+                       tf.TPIDR, info.source, info.kind, syndrome, tf.ELR );
+            backtrace::dump( tf, esr, aarch64::far() );
+            if SCHEDULER.switch( process::State::Dead, tf ).is_none() {
+                panic!( "no process to switch to after killing process {}", tf.TPIDR );
             }
-        }
-        _ => {},
+        },
+        Action::Report => {
+            kprintln!( "process {}: unexpected {:?} exception at {:#x}", tf.TPIDR, info.kind, tf.ELR );
+        },
+        Action::Panic => {
+            backtrace::dump( tf, esr, aarch64::far() );
+            panic!( "unexpected {:?} {:?} exception: {:?} at {:#x}", info.source, info.kind, syndrome, tf.ELR );
+        },
     }
+
+    //act on signals of the process about to be resumed at EL0
+    process::signal::deliver( tf );
 }
@@ -0,0 +1,99 @@
+use super::{Source, Kind};
+use super::syndrome::Syndrome;
+
+/// The class of a syndrome, without its payload, as matched by the policy
+/// table.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Class {
+    Svc,
+    Brk,
+    /// Instruction and data aborts, PC and SP alignment faults.
+    Abort,
+    Other,
+}
+
+impl Class {
+    pub fn of(syndrome: Syndrome) -> Class {
+        match syndrome {
+            Syndrome::Svc(_) => Class::Svc,
+            Syndrome::Brk(_) => Class::Brk,
+            Syndrome::InstructionAbort { .. } |
+            Syndrome::DataAbort { .. } |
+            Syndrome::PCAlignmentFault |
+            Syndrome::SpAlignmentFault => Class::Abort,
+            _ => Class::Other,
+        }
+    }
+}
+
+/// What the kernel does with an exception.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Action {
+    /// Handle the system call. `ELR` already holds the address of the
+    /// instruction after `svc`.
+    Syscall,
+    /// Enter the debug shell, then step over the `brk` instruction, which
+    /// `ELR` points to.
+    Debug,
+    /// Dispatch the pending interrupts and resume where the process was
+    /// interrupted.
+    Irq,
+    /// Report the fault and kill the process that caused it. The faulting
+    /// instruction is never returned to.
+    Kill,
+    /// Report the exception and resume where it was taken.
+    Report,
+    /// Panic with the full context of the exception: a kernel bug or a
+    /// misconfigured exception.
+    Panic,
+}
+
+/// An entry of the policy table. `None` matches any source or class.
+struct Rule {
+    source: Option<Source>,
+    kind: Kind,
+    class: Option<Class>,
+    action: Action,
+}
+
+const fn rule(source: Option<Source>, kind: Kind, class: Option<Class>, action: Action) -> Rule {
+    Rule { source, kind, class, action }
+}
+
+/// The policy table, searched in order; exceptions matching no rule panic.
+///
+/// Kernel threads run at EL1t, so their exceptions come from `CurrentSpEl0`.
+/// They make system calls like user processes, but any fault of theirs is a
+/// kernel bug. AArch32 processes are not supported: any synchronous exception
+/// from one kills it. FIQs are never enabled, so one taken from a process is
+/// only reported.
+static POLICY: [Rule; 10] = [
+    rule( Some( Source::LowerAArch64 ), Kind::Synchronous, Some( Class::Svc ), Action::Syscall ),
+    rule( Some( Source::CurrentSpEl0 ), Kind::Synchronous, Some( Class::Svc ), Action::Syscall ),
+    rule( None, Kind::Synchronous, Some( Class::Brk ), Action::Debug ),
+    rule( None, Kind::Irq, None, Action::Irq ),
+    rule( Some( Source::LowerAArch64 ), Kind::Synchronous, None, Action::Kill ),
+    rule( Some( Source::LowerAArch32 ), Kind::Synchronous, None, Action::Kill ),
+    rule( Some( Source::LowerAArch64 ), Kind::SError, None, Action::Kill ),
+    rule( Some( Source::LowerAArch32 ), Kind::SError, None, Action::Kill ),
+    rule( Some( Source::LowerAArch64 ), Kind::Fiq, None, Action::Report ),
+    rule( Some( Source::LowerAArch32 ), Kind::Fiq, None, Action::Report ),
+];
+
+/// Returns the action for an exception of `kind` taken from `source`.
+/// `syndrome` is only matched for synchronous exceptions, the only ones with
+/// a valid `ESR`.
+pub fn action(source: Source, kind: Kind, syndrome: Syndrome) -> Action {
+    let class = match kind {
+        Kind::Synchronous => Some( Class::of( syndrome ) ),
+        _ => None,
+    };
+
+    POLICY.iter()
+        .find( |r| {
+            r.kind == kind &&
+            r.source.map_or( true, |s| s == source ) &&
+            r.class.map_or( true, |c| Some( c ) == class )
+        })
+        .map_or( Action::Panic, |r| r.action )
+}
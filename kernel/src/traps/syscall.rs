@@ -1,4 +1,74 @@
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use abi::{self, nr, OsError, NO_TIMEOUT};
+use abi::clock::NANOS_PER_SEC;
+use abi::seek::{SEEK_SET, SEEK_CUR, SEEK_END};
+use abi::signal::{SIG_DFL, SIG_IGN, SIG_BLOCK, SIG_UNBLOCK, SIG_SETMASK};
+use ipc::{Pipe, PIPE_SIZE, Transfer, Message, MSG_SIZE, Region, SharedMemory, ShmError};
+use mutex::IrqMutex;
+use process::{self, Process, Descriptor, Fd, File, Resource, AffinityError, UserPtr, UserSlice, Access, TICK};
+use process::signal::{self, Action, Signal};
+use sync::{Futexes, Sched};
+#[cfg(not(test))]
+use sync::KernelSched;
+#[cfg(test)]
+use sync::sim::SimSched;
 use traps::TrapFrame;
+use clock;
+use console::{CONSOLE, CONSOLE_RX};
+use SCHEDULER;
+
+/// The scheduler the system calls run against. Tests run them against the
+/// simulated one.
+#[cfg(not(test))]
+type Kernel = KernelSched;
+#[cfg(test)]
+type Kernel = SimSched;
+
+/// Processes blocked in `futex_wait`.
+static FUTEXES: Futexes = Futexes::new();
+
+/// Named shared memory regions.
+static SHARED_MEMORY: SharedMemory = SharedMemory::new();
+
+/// Reports success in the status register `x7` of `tf`.
+fn ok(tf: &mut TrapFrame) {
+    tf.x1_x29[6] = abi::OK;
+}
+
+/// Reports `e` in the status register `x7` of `tf`.
+fn fail(tf: &mut TrapFrame, e: OsError) {
+    tf.x1_x29[6] = e.code();
+}
+
+/// Runs `f` on the process executing in `tf`. Fails with `NoSuchProcess` if
+/// it is gone.
+fn with_caller<R, F: FnOnce(&mut Process) -> Result<R, OsError>>(tf: &TrapFrame, f: F) -> Result<R, OsError> {
+    Kernel::with_process( tf.TPIDR, f ).unwrap_or( Err( OsError::NoSuchProcess ) )
+}
+
+/// Switches the process executing in `tf` out of the CPU until `poll`
+/// completes its system call `num`. If the process is traced, the completed
+/// call is logged with the time since it was made, once the scheduler lock is
+/// released.
+fn wait<F>(num: u16, mut poll: F, tf: &mut TrapFrame)
+    where F: FnMut(&mut Process) -> bool + Send + 'static
+{
+    let ( args, start ) = ( syscall_args( tf ), Kernel::now() );
+    Kernel::switch( process::State::Waiting(
+        Box::new( move |p| {
+            if !poll( p ) {
+                return false
+            }
+            if let ( true, Some(&Some(ref syscall)) ) = ( p.traced, SYSCALLS.get( num as usize ) ) {
+                let us = Kernel::now().saturating_sub( start );
+                p.trace = Some( describe( p.trap_frame.TPIDR, syscall, &args, &p.trap_frame, us ) );
+            }
+            true
+        })
+    ), tf );
+}
 
 /// Sleep for `ms` milliseconds.
 ///
@@ -6,65 +76,1212 @@ use traps::TrapFrame;
 ///
 /// In addition to the usual status value, this system call returns one
 /// parameter: the approximate true elapsed time from when `sleep` was called to
-/// when `sleep` returned.
+/// when `sleep` returned. A signal interrupts the sleep with `Interrupted`.
 pub fn sleep(ms: u32, tf: &mut TrapFrame) {
-
-    use console::kprintln;
-    use pi::timer;
-    use process;
-    use SCHEDULER;
-    use std::ptr;
-    
     let t_us = ( ms as u64 ) * 1000;
-    let t_start = timer::current_time();
+    let t_start = Kernel::now();
     let t_expect_end = t_start + t_us;
     
     //do not reschedule until poll succeeds
-    SCHEDULER.switch( process::State::Waiting(
-        Box::new( move |p| {
-            let t_now = timer::current_time();
-            let diff : u64 = t_now.saturating_sub( t_expect_end );
-            if  diff >= t_us {
-                p.trap_frame.x0 = (diff / 1000); //return approx sleep time in ms
-                p.trap_frame.x1_x29[6] = 0; //set return status to success
-                true
-            } else {
-                false
+    wait( nr::SLEEP, move |p| {
+        let t_now = Kernel::now();
+        if t_now >= t_expect_end {
+            p.trap_frame.x0 = ( t_now - t_start ) / 1000; //return approx sleep time in ms
+            ok( &mut p.trap_frame );
+            true
+        } else if p.signals.is_interrupted() {
+            p.trap_frame.x0 = t_now.saturating_sub( t_start ) / 1000;
+            fail( &mut p.trap_frame, OsError::Interrupted );
+            true
+        } else {
+            false
+        }
+    }, tf );
+}
+
+/// Exit the calling process.
+///
+/// This system call takes no parameters and does not return. The process is
+/// removed from the scheduler and its stack is released.
+pub fn exit(tf: &mut TrapFrame) {
+
+    use process;
+
+    Kernel::switch( process::State::Dead, tf );
+}
+
+/// Returns the futex word at `addr` of the process executing in `tf`. Fails
+/// with `InvalidArgument` if `addr` is not aligned to 4 bytes and with
+/// `BadAddress` if it is outside of the memory of the process.
+fn futex_word(addr: u64, tf: &TrapFrame) -> Result<*const u32, OsError> {
+    if addr % 4 != 0 {
+        return Err( OsError::InvalidArgument )
+    }
+    with_caller( tf, |p| Ok( UserPtr::<u32>::new( addr ).check( p, Access::Read )? as *const u32 ) )
+}
+
+/// Wait on the futex word at `addr` while it holds `expected`.
+///
+/// This system call takes two parameters: the address of a 4-byte aligned
+/// 32-bit word and the value the caller expects it to hold. If the word holds
+/// a different value, the call returns `WouldBlock` immediately. Otherwise the
+/// process is blocked until a `futex_wake` on the same address wakes it up.
+/// Fails with `Interrupted` if a signal arrives first.
+///
+/// This system call returns no parameters.
+pub fn futex_wait(addr: u64, expected: u32, tf: &mut TrapFrame) {
+    let word = match futex_word( addr, tf ) {
+        Ok(x) => x,
+        Err(e) => {
+            fail( tf, e );
+            return
+        }
+    };
+
+    //the status is saved along with the process if it blocks, and replaced
+    //if a signal interrupts it
+    ok( tf );
+    if !unsafe { FUTEXES.wait( word, expected, tf ) } {
+        fail( tf, OsError::WouldBlock );
+    }
+}
+
+/// Wake up at most `n` processes waiting on the futex word at `addr`.
+///
+/// This system call takes two parameters: the address of the futex word and
+/// the maximum number of processes to wake.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the number of processes woken.
+pub fn futex_wake(addr: u64, n: usize, tf: &mut TrapFrame) {
+    match futex_word( addr, tf ) {
+        Ok(word) => {
+            tf.x0 = FUTEXES.wake( word, n ) as u64;
+            ok( tf );
+        },
+        Err(e) => fail( tf, e ),
+    }
+}
+
+/// Returns the pipe open under `fd` in the process executing in `tf`, and
+/// `true` if it is the write end.
+fn pipe_end(fd: u64, tf: &TrapFrame) -> Option<(Arc<Pipe>, bool)> {
+    Kernel::with_process( tf.TPIDR, |p| {
+        match p.files.get( fd as Fd ) {
+            Some(&Descriptor::PipeRead(ref x)) => Some( ( x.clone(), false ) ),
+            Some(&Descriptor::PipeWrite(ref x)) => Some( ( x.clone(), true ) ),
+            Some(&Descriptor::File(_)) | Some(&Descriptor::Console) | None => None,
+        }
+    })?
+}
+
+/// Returns `true` if the console is open under `fd` in the process executing
+/// in `tf`.
+fn is_console(fd: u64, tf: &TrapFrame) -> bool {
+    Kernel::with_process( tf.TPIDR, |p| {
+        match p.files.get( fd as Fd ) {
+            Some(&Descriptor::Console) => true,
+            _ => false,
+        }
+    }).unwrap_or( false )
+}
+
+/// Reads the bytes received by the console into `buf` for the process
+/// executing in `tf`. Blocks the process on `CONSOLE_RX` if nothing has been
+/// received yet.
+fn console_read(buf: &mut [u8], tf: &mut TrapFrame) -> Transfer {
+    let mut console = CONSOLE.lock();
+    let mut n = 0;
+    while n < buf.len() && console.has_byte() {
+        buf[n] = console.read_byte();
+        n += 1;
+    }
+
+    if n > 0 || buf.is_empty() {
+        return Transfer::Done(n)
+    }
+
+    //blocked before the console is unlocked so that the next receive
+    //interrupt cannot be missed
+    CONSOLE_RX.wait( tf );
+    drop( console );
+    Transfer::Blocked
+}
+
+/// Returns the file of the file system open under `fd` in the process
+/// executing in `tf`.
+fn fs_file(fd: u64, tf: &TrapFrame) -> Option<Arc<IrqMutex<File>>> {
+    Kernel::with_process( tf.TPIDR, |p| {
+        match p.files.get( fd as Fd ) {
+            Some(&Descriptor::File(ref x)) => Some( x.clone() ),
+            _ => None,
+        }
+    })?
+}
+
+/// Returns the error reported for the file system error `e`.
+fn io_error(e: &io::Error) -> OsError {
+    match e.kind() {
+        io::ErrorKind::NotFound => OsError::NotFound,
+        io::ErrorKind::InvalidInput => OsError::InvalidArgument,
+        _ => OsError::Io,
+    }
+}
+
+/// Runs the pipe transfer `f` on behalf of the process executing in `tf`. If
+/// the process blocks, it executes the system call again once woken up.
+fn transfer<F: FnOnce(&mut TrapFrame) -> Transfer>(tf: &mut TrapFrame, f: F) {
+    tf.ELR -= 4; //back to the `svc` instruction
+    match f( tf ) {
+        Transfer::Blocked => {},
+        Transfer::Done(n) => {
+            tf.ELR += 4;
+            tf.x0 = n as u64;
+            ok( tf );
+        },
+        Transfer::Broken => {
+            tf.ELR += 4;
+            fail( tf, OsError::BrokenPipe );
+        },
+    }
+}
+
+/// Create a pipe.
+///
+/// This system call takes no parameters.
+///
+/// In addition to the usual status value, this system call returns two
+/// parameters: the file descriptor of the read end in `x0` and of the write
+/// end in `x1`. Fails with `TooManyFiles` if the process cannot open two more files.
+pub fn pipe(tf: &mut TrapFrame) {
+    if !can_use( Resource::Files, 2, tf ) {
+        fail( tf, OsError::TooManyFiles );
+        return
+    }
+
+    let pipe = Arc::new( Pipe::new() );
+    let fds = Kernel::with_process( tf.TPIDR, |p| {
+        let r = p.files.insert( Descriptor::PipeRead( pipe.clone() ) );
+        let w = p.files.insert( Descriptor::PipeWrite( pipe ) );
+        ( r, w )
+    });
+
+    match fds {
+        Some( ( r, w ) ) => {
+            tf.x0 = r as u64;
+            tf.x1_x29[0] = w as u64;
+            ok( tf );
+        },
+        None => fail( tf, OsError::InvalidArgument ),
+    }
+}
+
+/// Returns `true` if the process executing in `tf` can use `amount` more of
+/// `r` within its limit.
+fn can_use(r: Resource, amount: u64, tf: &TrapFrame) -> bool {
+    Kernel::with_process( tf.TPIDR, |p| p.can_use( r, amount ) ).unwrap_or( false )
+}
+
+/// Read from the file open under `fd`.
+///
+/// This system call takes three parameters: the file descriptor, the address
+/// of the buffer to read into and its length. Reading from an empty pipe
+/// blocks until data is written or every write end is closed. Reading from a
+/// file starts at its position and advances it. Reading from the console
+/// waits until at least one byte has been received.
+///
+/// At most `PIPE_SIZE` bytes are read at once. Fails with `BadAddress` if the
+/// buffer is outside of the memory of the process and with `Io` if the file
+/// system fails.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the number of bytes read, `0` at end-of-file.
+pub fn read(fd: u64, addr: u64, len: u64, tf: &mut TrapFrame) {
+    let buf = UserSlice::new( addr, len );
+    if let Err(e) = with_caller( tf, |p| Ok( buf.check( p, Access::Write )? ) ) {
+        fail( tf, e );
+        return
+    }
+
+    let mut data = [0u8; PIPE_SIZE];
+    let n = buf.len().min( PIPE_SIZE );
+
+    if let Some(file) = fs_file( fd, tf ) {
+        let mut file = file.lock();
+        let read = file.seek( SeekFrom::Current( 0 ) )
+            .and_then( |pos| file.read( &mut data[..n] ).map( |n| ( pos, n ) ) );
+        match read {
+            Ok( ( pos, n ) ) => {
+                match with_caller( tf, |p| Ok( buf.copy_to_user( p, &data[..n] )? ) ) {
+                    Ok(()) => {
+                        tf.x0 = n as u64;
+                        ok( tf );
+                    },
+                    Err(e) => {
+                        //the data never reached the process: read it again next time
+                        let _ = file.seek( SeekFrom::Start( pos ) );
+                        fail( tf, e );
+                    },
+                }
+            },
+            Err(e) => fail( tf, io_error( &e ) ),
+        }
+        return
+    }
+
+    let pipe = match pipe_end( fd, tf ) {
+        Some( ( pipe, false ) ) => Some( pipe ),
+        _ if is_console( fd, tf ) => None,
+        _ => {
+            fail( tf, OsError::BadFileDescriptor );
+            return
+        },
+    };
+
+    let mut copied = Ok(());
+    transfer( tf, |tf| {
+        let t = match pipe {
+            Some(ref pipe) => pipe.read( &mut data[..n], tf ),
+            None => console_read( &mut data[..n], tf ),
+        };
+        if let Transfer::Done(n) = t {
+            copied = with_caller( tf, |p| Ok( buf.copy_to_user( p, &data[..n] )? ) );
+        }
+        t
+    });
+
+    //only set once the transfer is done, so `tf` still belongs to the caller
+    if let Err(e) = copied {
+        fail( tf, e );
+    }
+}
+
+/// Write to the file open under `fd`.
+///
+/// This system call takes three parameters: the file descriptor, the address
+/// of the data to write and its length. Writing to a full pipe blocks until
+/// data is read. Writing to a pipe without readers fails with `BrokenPipe`.
+/// Writing to the console sends the data out of the UART before returning,
+/// with each `\n` preceded by `\r`.
+/// Files are opened read-only: writing to one fails with `BadFileDescriptor`.
+///
+/// At most `PIPE_SIZE` bytes are written at once. Fails with `BadAddress` if
+/// the data is outside of the memory of the process.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the number of bytes written.
+pub fn write(fd: u64, addr: u64, len: u64, tf: &mut TrapFrame) {
+    let buf = UserSlice::new( addr, len );
+    let mut data = [0u8; PIPE_SIZE];
+    let n = buf.len().min( PIPE_SIZE );
+    if let Err(e) = with_caller( tf, |p| Ok( buf.copy_from_user( p, &mut data[..n] )? ) ) {
+        fail( tf, e );
+        return
+    }
+
+    match pipe_end( fd, tf ) {
+        Some( ( pipe, true ) ) => transfer( tf, |tf| pipe.write( &data[..n], tf ) ),
+        _ if is_console( fd, tf ) => {
+            let mut console = CONSOLE.lock();
+            for b in data[..n].iter() {
+                if *b == b'\n' {
+                    console.write_byte( b'\r' ); //like `kprint!`
+                }
+                console.write_byte( *b );
             }
-        })
-    ), tf );
+            tf.x0 = n as u64;
+            ok( tf );
+        },
+        _ => fail( tf, OsError::BadFileDescriptor ),
+    }
 }
 
-///to be called by userland
-pub fn syscall_sleep_ms( ms: u32 ) -> u32 {
+/// Open the file at `path` for reading.
+///
+/// This system call takes two parameters: the address and length of the
+/// absolute UTF-8 path of the file. The file is read from its start. Fails
+/// with `NotFound` if there is no such file, with `InvalidArgument` if the
+/// path is relative or names a directory, and with `TooManyFiles` if the
+/// process cannot open another file.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the file descriptor.
+pub fn open(path_addr: u64, path_len: u64, tf: &mut TrapFrame) {
+    use fs::traits::{FileSystem, Entry};
+    use FILE_SYSTEM;
+
+    let path = match user_str( path_addr, path_len, tf ) {
+        Ok(x) => x,
+        Err(e) => {
+            fail( tf, e );
+            return
+        },
+    };
+    if !path.starts_with( '/' ) {
+        fail( tf, OsError::InvalidArgument );
+        return
+    }
+    if !can_use( Resource::Files, 1, tf ) {
+        fail( tf, OsError::TooManyFiles );
+        return
+    }
 
-    let mut ret : u64;
-    let mut err_code : u64;
+    let file = match (&FILE_SYSTEM).open( &path ).map( |x| x.into_file() ) {
+        Ok(Some(x)) => x,
+        Ok(None) => {
+            fail( tf, OsError::InvalidArgument );
+            return
+        },
+        Err(e) => {
+            fail( tf, io_error( &e ) );
+            return
+        },
+    };
 
-    //input: sleep time
-    //output: actual sleep time, error code
-    unsafe {
-        asm!("mov x0, $2
-              svc 1
-              mov $0, x0
-              mov $1, x7"
-             : "=r"(ret), "=r"(err_code) : "r"(ms) :"x0","x7": );
+    let d = Descriptor::File( Arc::new( IrqMutex::new( file ) ) );
+    match Kernel::with_process( tf.TPIDR, |p| p.files.insert( d ) ) {
+        Some(fd) => {
+            tf.x0 = fd as u64;
+            ok( tf );
+        },
+        None => fail( tf, OsError::NoSuchProcess ),
     }
+}
+
+/// Move the position of the file open under `fd`.
+///
+/// This system call takes three parameters: the file descriptor, the offset
+/// and `SEEK_SET`, `SEEK_CUR` or `SEEK_END` for an offset from the start, the
+/// current position or the end of the file. The offset is signed for
+/// `SEEK_CUR` and `SEEK_END`. Fails with `BadFileDescriptor` for pipes and
+/// with `InvalidArgument` for a position before the start of the file.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the new position from the start of the file.
+pub fn seek(fd: u64, offset: u64, whence: u64, tf: &mut TrapFrame) {
+    let from = match whence {
+        SEEK_SET => SeekFrom::Start( offset ),
+        SEEK_CUR => SeekFrom::Current( offset as i64 ),
+        SEEK_END => SeekFrom::End( offset as i64 ),
+        _ => {
+            fail( tf, OsError::InvalidArgument );
+            return
+        },
+    };
+
+    let file = match fs_file( fd, tf ) {
+        Some(x) => x,
+        None => {
+            fail( tf, OsError::BadFileDescriptor );
+            return
+        },
+    };
 
-    if err_code != 0 {
-        panic!("syscall sleep failed");
+    let pos = file.lock().seek( from );
+    match pos {
+        Ok(pos) => {
+            tf.x0 = pos;
+            ok( tf );
+        },
+        Err(e) => fail( tf, io_error( &e ) ),
     }
+}
 
-    ret as u32
+/// Get the arguments of the calling process.
+///
+/// This system call takes two parameters: the address and length of a buffer.
+/// The arguments, starting with the name of the process, are copied to the
+/// buffer, each followed by a NUL byte, if the buffer can hold all of them.
+/// Otherwise nothing is copied. Fails with `BadAddress` if the buffer is
+/// outside of the memory of the process.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the number of bytes the arguments take.
+pub fn args(addr: u64, len: u64, tf: &mut TrapFrame) {
+    let buf = UserSlice::new( addr, len );
+    let size = with_caller( tf, |p| {
+        let bytes = {
+            let args = || Some( &p.name ).into_iter().chain( p.args.iter() ).map( |x| x.as_bytes() );
+            let mut bytes = vec![ 0; abi::args::packed_len( args() ) ];
+            abi::args::pack( args(), &mut bytes );
+            bytes
+        };
+        if bytes.len() <= buf.len() {
+            buf.copy_to_user( p, &bytes )?;
+        }
+        Ok( bytes.len() )
+    });
+
+    match size {
+        Ok(size) => {
+            tf.x0 = size as u64;
+            ok( tf );
+        },
+        Err(e) => fail( tf, e ),
+    }
+}
+
+/// Close the file open under `fd`.
+///
+/// This system call takes one parameter: the file descriptor. It returns no
+/// parameters.
+pub fn close(fd: u64, tf: &mut TrapFrame) {
+    let file = Kernel::with_process( tf.TPIDR, |p| p.files.remove( fd as Fd ) );
+
+    //dropped here, outside of the scheduler lock, as closing may wake up
+    //processes blocked on the other end
+    match file {
+        Some(Some(_)) => ok( tf ),
+        _ => fail( tf, OsError::BadFileDescriptor ),
+    }
+}
+
+/// Send a message of `MSG_SIZE` bytes to the process with ID `pid`.
+///
+/// This system call takes two parameters: the ID of the receiving process and
+/// the address of the message. The message is copied into the receiver's
+/// mailbox. The call does not block: if the mailbox is full, it fails with
+/// `WouldBlock`, and if there is no such process, with `NoSuchProcess`.
+/// Fails with `BadAddress` if the message is outside of the memory of the
+/// process.
+///
+/// This system call returns no parameters.
+pub fn send(pid: u64, addr: u64, tf: &mut TrapFrame) {
+    let data = match with_caller( tf, |p| Ok( UserPtr::<[u8; MSG_SIZE]>::new( addr ).read( p )? ) ) {
+        Ok(x) => x,
+        Err(e) => {
+            fail( tf, e );
+            return
+        },
+    };
+
+    let msg = Message { sender: tf.TPIDR, data };
+
+    match Kernel::with_process( pid, |p| p.mailbox.push( msg ) ) {
+        Some(Ok(())) => ok( tf ),
+        Some(Err(_)) => fail( tf, OsError::WouldBlock ),
+        None => fail( tf, OsError::NoSuchProcess ),
+    }
+}
+
+/// Copies the next message of `p`, if any, to `buf` in its memory. Returns
+/// the result of `recv`: the ID of the sender.
+fn deliver(buf: &UserPtr<[u8; MSG_SIZE]>, p: &mut Process) -> Option<Result<u64, OsError>> {
+    //checked first so that the message is not lost
+    if let Err(e) = buf.check( p, Access::Write ) {
+        return Some( Err( e.into() ) )
+    }
+    let msg = p.mailbox.pop()?;
+    Some( buf.write( p, msg.data ).map( |()| msg.sender ).map_err( |e| e.into() ) )
+}
+
+/// Sets `result` as the result of `recv` in `tf`.
+fn received(result: Result<u64, OsError>, tf: &mut TrapFrame) {
+    match result {
+        Ok(sender) => {
+            tf.x0 = sender;
+            ok( tf );
+        },
+        Err(e) => fail( tf, e ),
+    }
+}
+
+/// Receive the oldest message sent to the calling process.
+///
+/// This system call takes two parameters: the address of a buffer of
+/// `MSG_SIZE` bytes and a timeout in milliseconds. If the mailbox is empty,
+/// the process waits for a message for up to the timeout, like `sleep`. A
+/// timeout of `0` fails right away with `WouldBlock`, and `NO_TIMEOUT` waits
+/// until a message arrives. A wait that times out fails with `TimedOut`, and
+/// one interrupted by a signal with `Interrupted`. Fails with `BadAddress` if
+/// the buffer is outside of the memory of the process.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the ID of the sending process.
+pub fn recv(addr: u64, timeout_ms: u64, tf: &mut TrapFrame) {
+
+    let buf = UserPtr::<[u8; MSG_SIZE]>::new( addr );
+
+    match Kernel::with_process( tf.TPIDR, |p| deliver( &buf, p ) ) {
+        Some(Some(result)) => return received( result, tf ),
+        Some(None) => {},
+        None => {
+            fail( tf, OsError::NoSuchProcess );
+            return
+        },
+    }
+
+    if timeout_ms == 0 {
+        fail( tf, OsError::WouldBlock );
+        return
+    }
+
+    let t_end = match timeout_ms {
+        NO_TIMEOUT => None,
+        ms => Some( Kernel::now().saturating_add( ms.saturating_mul( 1000 ) ) ),
+    };
+
+    //do not reschedule until a message arrives or the timeout expires
+    wait( nr::RECV, move |p| {
+        if let Some(result) = deliver( &buf, p ) {
+            received( result, &mut p.trap_frame );
+            true
+        } else if t_end.map_or( false, |t| Kernel::now() >= t ) {
+            fail( &mut p.trap_frame, OsError::TimedOut );
+            true
+        } else if p.signals.is_interrupted() {
+            fail( &mut p.trap_frame, OsError::Interrupted );
+            true
+        } else {
+            false
+        }
+    }, tf );
 }
 
+/// Copies the UTF-8 string of `len` bytes at `addr` out of the memory of the
+/// process executing in `tf`. Fails with `BadAddress` if the string is outside
+/// of the memory of the process and with `InvalidArgument` if it is not valid
+/// UTF-8.
+fn user_str(addr: u64, len: u64, tf: &TrapFrame) -> Result<String, OsError> {
+    let bytes = with_caller( tf, |p| Ok( UserSlice::new( addr, len ).to_vec( p )? ) )?;
+    String::from_utf8( bytes ).map_err( |_| OsError::InvalidArgument )
+}
+
+/// Create a shared memory region and map it into the calling process.
+///
+/// This system call takes three parameters: the address and length of the
+/// UTF-8 name of the region, and its size in bytes, rounded up to a multiple
+/// of `PAGE_SIZE`. Fails with `AlreadyExists` if a region with the same name is
+/// still mapped by some process. Fails with `NoMemory` if the region would take
+/// the process over its memory limit.
+///
+/// In addition to the usual status value, this system call returns two
+/// parameters: the address of the region and its size.
+pub fn shm_create(name_addr: u64, name_len: u64, size: u64, tf: &mut TrapFrame) {
+    let name = match user_str( name_addr, name_len, tf ) {
+        Ok(x) => x,
+        Err(e) => {
+            fail( tf, e );
+            return
+        },
+    };
+
+    //refuse before the region is taken out of the kernel heap
+    if !can_use( Resource::Memory, size, tf ) {
+        fail( tf, OsError::NoMemory );
+        return
+    }
+
+    match SHARED_MEMORY.create( &name, size as usize ) {
+        Ok(region) => map_region( region, tf ),
+        Err(ShmError::Exists) => fail( tf, OsError::AlreadyExists ),
+        Err(ShmError::NoMemory) => fail( tf, OsError::NoMemory ),
+    }
+}
+
+/// Map an existing shared memory region into the calling process.
+///
+/// This system call takes two parameters: the address and length of the
+/// UTF-8 name of the region. Fails with `NotFound` if there is no such region.
+/// A process may map the same region more than once; each mapping is dropped
+/// separately.
+///
+/// In addition to the usual status value, this system call returns two
+/// parameters: the address of the region and its size.
+pub fn shm_map(name_addr: u64, name_len: u64, tf: &mut TrapFrame) {
+    let name = match user_str( name_addr, name_len, tf ) {
+        Ok(x) => x,
+        Err(e) => {
+            fail( tf, e );
+            return
+        },
+    };
+
+    match SHARED_MEMORY.open( &name ) {
+        Some(region) => map_region( region, tf ),
+        None => fail( tf, OsError::NotFound ),
+    }
+}
+
+/// Adds `region` to the mappings of the process executing in `tf` and returns
+/// its address and size. Fails with `NoMemory` if the mapping would take the
+/// process over its memory limit.
+fn map_region(region: Arc<Region>, tf: &mut TrapFrame) {
+    let ( start, size ) = ( region.start().as_u64(), region.size() as u64 );
+    let refused = Kernel::with_process( tf.TPIDR, |p| {
+        if p.can_use( Resource::Memory, size ) {
+            p.regions.push( region );
+            None
+        } else {
+            Some( region )
+        }
+    });
+
+    //a refused region is released here, outside of the scheduler lock
+    match refused {
+        Some(None) => {
+            tf.x0 = start;
+            tf.x1_x29[0] = size;
+            ok( tf );
+        },
+        Some(Some(_)) => fail( tf, OsError::NoMemory ),
+        None => fail( tf, OsError::NoSuchProcess ),
+    }
+}
+
+/// Drop a mapping of a shared memory region from the calling process.
+///
+/// This system call takes one parameter: the address of the region. The
+/// region is released once no process maps it anymore.
+///
+/// This system call returns no parameters.
+pub fn shm_drop(addr: u64, tf: &mut TrapFrame) {
+    let region = Kernel::with_process( tf.TPIDR, |p| {
+        let i = p.regions.iter().position( |r| r.start().as_u64() == addr )?;
+        Some( p.regions.swap_remove( i ) )
+    });
+
+    //released here, outside of the scheduler lock, if this was the last mapping
+    match region {
+        Some(Some(_)) => ok( tf ),
+        _ => fail( tf, OsError::InvalidArgument ),
+    }
+}
+
+/// Map `size` bytes of private, zeroed memory into the calling process.
+///
+/// This system call takes one parameter: the size in bytes, rounded up to a
+/// multiple of `PAGE_SIZE`. Fails with `NoMemory` if the memory would take
+/// the process over its memory limit or cannot be allocated.
+///
+/// In addition to the usual status value, this system call returns two
+/// parameters: the page-aligned address of the memory and its size.
+pub fn mmap(size: u64, tf: &mut TrapFrame) {
+    if size == 0 {
+        return fail( tf, OsError::InvalidArgument )
+    }
+
+    //refuse before the memory is taken out of the kernel heap
+    if !can_use( Resource::Memory, size, tf ) {
+        return fail( tf, OsError::NoMemory )
+    }
+
+    match Region::anonymous( size as usize ) {
+        Some(region) => map_region( Arc::new( region ), tf ),
+        None => fail( tf, OsError::NoMemory ),
+    }
+}
+
+/// Unmap the memory at `addr` returned by `mmap`.
+///
+/// This system call takes one parameter: the address of the memory. It is
+/// released right away. Fails with `InvalidArgument` if nothing is mapped at
+/// `addr`.
+///
+/// This system call returns no parameters.
+pub fn munmap(addr: u64, tf: &mut TrapFrame) {
+    //anonymous memory is a region only the calling process maps
+    shm_drop( addr, tf )
+}
+
+/// Send signal `sig` to the process with ID `pid`.
+///
+/// This system call takes two parameters: the ID of the receiving process and
+/// the signal number. Fails with `NoSuchProcess` if there is no such process
+/// and with `PermissionDenied` if it is a kernel thread or an idle process.
+///
+/// This system call returns no parameters.
+pub fn kill(pid: u64, sig: Signal, tf: &mut TrapFrame) {
+    if !signal::is_valid( sig ) {
+        fail( tf, OsError::InvalidArgument )
+    } else if Kernel::with_process( pid, |p| p.is_kernel() ) == Some( true ) {
+        fail( tf, OsError::PermissionDenied )
+    } else if signal::kill( pid, sig ) {
+        ok( tf )
+    } else {
+        fail( tf, OsError::NoSuchProcess )
+    }
+}
+
+/// Set the action of signal `sig` in the calling process.
+///
+/// This system call takes three parameters: the signal number, the handler,
+/// either `SIG_DFL`, `SIG_IGN` or the address of a function taking the signal
+/// number, and the address the handler returns to, which must issue
+/// `sigreturn`. The action of `SIGKILL` cannot be changed.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the previous handler.
+pub fn sigaction(sig: Signal, handler: u64, restorer: u64, tf: &mut TrapFrame) {
+    let action = match handler {
+        SIG_DFL => Action::Default,
+        SIG_IGN => Action::Ignore,
+        entry => Action::Handler { entry, restorer },
+    };
+
+    match Kernel::with_process( tf.TPIDR, |p| p.signals.set_action( sig, action ) ) {
+        Some(Some(old)) => {
+            tf.x0 = match old {
+                Action::Default => SIG_DFL,
+                Action::Ignore => SIG_IGN,
+                Action::Handler { entry, .. } => entry,
+            };
+            ok( tf );
+        },
+        _ => fail( tf, OsError::InvalidArgument ),
+    }
+}
+
+/// Change the blocked signal mask of the calling process.
+///
+/// This system call takes two parameters: `SIG_BLOCK`, `SIG_UNBLOCK` or
+/// `SIG_SETMASK`, and a mask with bit `n` set for signal `n`. `SIGKILL` cannot
+/// be blocked. Signals that become unblocked are delivered before the call
+/// returns.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the previous mask.
+pub fn sigprocmask(how: u64, set: u32, tf: &mut TrapFrame) {
+    let old = Kernel::with_process( tf.TPIDR, |p| {
+        let old = p.signals.blocked();
+        let new = match how {
+            SIG_BLOCK => old | set,
+            SIG_UNBLOCK => old & !set,
+            SIG_SETMASK => set,
+            _ => return None,
+        };
+        p.signals.set_blocked( new );
+        Some( old )
+    });
+
+    match old {
+        Some(Some(old)) => {
+            tf.x0 = old as u64;
+            ok( tf );
+        },
+        _ => fail( tf, OsError::InvalidArgument ),
+    }
+}
+
+/// Return from a signal handler.
+///
+/// This system call takes no parameters and does not return to its caller:
+/// the process resumes where it was interrupted by the signal, with the
+/// registers and blocked mask saved in the signal frame at the stack pointer.
+pub fn sigreturn(tf: &mut TrapFrame) {
+    signal::restore( tf );
+}
+
+/// Get the limit and current use of resource `resource` by the calling
+/// process.
+///
+/// This system call takes one parameter: the resource, `0` for memory in
+/// bytes, `1` for open files, `2` for children and `3` for CPU time in
+/// milliseconds.
+///
+/// In addition to the usual status value, this system call returns two
+/// parameters: the limit, `UNLIMITED` if there is none, and the amount used.
+pub fn getrlimit(resource: u64, tf: &mut TrapFrame) {
+    let r = match Resource::from_u64( resource ) {
+        Some(x) => x,
+        None => {
+            fail( tf, OsError::InvalidArgument );
+            return
+        },
+    };
+
+    match Kernel::with_process( tf.TPIDR, |p| ( p.limits.get( r ), p.usage( r ) ) ) {
+        Some( ( limit, used ) ) => {
+            tf.x0 = limit;
+            tf.x1_x29[0] = used;
+            ok( tf );
+        },
+        None => fail( tf, OsError::NoSuchProcess ),
+    }
+}
+
+/// Set the limit of resource `resource` for the calling process and the
+/// processes it creates afterwards.
+///
+/// This system call takes two parameters: the resource, as for `getrlimit`,
+/// and the new limit. Limits can only be lowered: fails with `PermissionDenied` if the
+/// new limit is higher than the current one.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the previous limit.
+pub fn setrlimit(resource: u64, limit: u64, tf: &mut TrapFrame) {
+    let r = match Resource::from_u64( resource ) {
+        Some(x) => x,
+        None => {
+            fail( tf, OsError::InvalidArgument );
+            return
+        },
+    };
+
+    match Kernel::with_process( tf.TPIDR, |p| p.limits.set( r, limit ) ) {
+        Some(Some(old)) => {
+            tf.x0 = old;
+            ok( tf );
+        },
+        Some(None) => fail( tf, OsError::PermissionDenied ),
+        None => fail( tf, OsError::NoSuchProcess ),
+    }
+}
+
+/// Set the cores the process with ID `pid` may run on.
+///
+/// This system call takes two parameters: the process ID and a mask with bit
+/// `n` set for each core `n` the process may run on. Only the calling process
+/// and its descendants may be pinned. Fails with `InvalidArgument` if the mask
+/// contains no online core. Fails with `PermissionDenied` for other processes
+/// and for idle processes, which are pinned to their core.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the previous mask.
+pub fn sched_setaffinity(pid: u64, mask: u64, tf: &mut TrapFrame) {
+    if !SCHEDULER.is_descendant( pid, tf.TPIDR ) {
+        //a missing process has no ancestors
+        let e = match Kernel::with_process( pid, |_| () ) {
+            Some(()) => OsError::PermissionDenied,
+            None => OsError::NoSuchProcess,
+        };
+        fail( tf, e );
+        return
+    }
+
+    match SCHEDULER.set_affinity( pid, mask as usize ) {
+        Ok(old) => {
+            tf.x0 = old as u64;
+            ok( tf );
+        },
+        Err(AffinityError::NoOnlineCore) => fail( tf, OsError::InvalidArgument ),
+        Err(AffinityError::Idle) => fail( tf, OsError::PermissionDenied ),
+        Err(AffinityError::NoSuchProcess) => fail( tf, OsError::NoSuchProcess ),
+    }
+}
+
+/// Get the cores the process with ID `pid` may run on.
+///
+/// This system call takes one parameter: the process ID.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the mask with bit `n` set for each core `n` the process may run
+/// on.
+pub fn sched_getaffinity(pid: u64, tf: &mut TrapFrame) {
+    match Kernel::with_process( pid, |p| p.affinity ) {
+        Some(mask) => {
+            tf.x0 = mask as u64;
+            ok( tf );
+        },
+        None => fail( tf, OsError::NoSuchProcess ),
+    }
+}
+
+/// Give up the CPU.
+///
+/// This system call takes no parameters. The calling process goes to the back
+/// of its run queue and the next ready process runs for the rest of the
+/// current time slice. If no other process is ready, the caller continues.
+///
+/// This system call returns no parameters.
+pub fn sched_yield(tf: &mut TrapFrame) {
+    //the status is saved along with the process
+    ok( tf );
+    Kernel::switch( process::State::Ready, tf );
+}
+
+/// Get the scheduling parameters of the process with ID `pid`.
+///
+/// This system call takes one parameter: the process ID. Fails with
+/// `NoSuchProcess` if there is no such process.
+///
+/// In addition to the usual status value, this system call returns two
+/// parameters: the length of a time slice in microseconds in `x0` and the
+/// priority of the process in `x1`. Every process is scheduled round robin
+/// with priority `0`.
+pub fn sched_getparam(pid: u64, tf: &mut TrapFrame) {
+    match Kernel::with_process( pid, |_| () ) {
+        Some(()) => {
+            tf.x0 = TICK as u64;
+            tf.x1_x29[0] = 0;
+            ok( tf );
+        },
+        None => fail( tf, OsError::NoSuchProcess ),
+    }
+}
+
+/// Get the ID of the calling process.
+///
+/// This system call takes no parameters.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the process ID.
+pub fn getpid(tf: &mut TrapFrame) {
+    tf.x0 = tf.TPIDR;
+    ok( tf );
+}
+
+/// Get the ID of the parent of the calling process.
+///
+/// This system call takes no parameters. Fails with `NoSuchProcess` if the process has
+/// no parent, either because it was started by the kernel or because its
+/// parent exited.
+///
+/// In addition to the usual status value, this system call returns one
+/// parameter: the parent process ID.
+pub fn getppid(tf: &mut TrapFrame) {
+    match Kernel::with_process( tf.TPIDR, |p| p.parent ) {
+        Some(Some(pid)) => {
+            tf.x0 = pid;
+            ok( tf );
+        },
+        _ => fail( tf, OsError::NoSuchProcess ),
+    }
+}
+
+/// Splits `us` microseconds into seconds and nanoseconds.
+fn to_timespec(us: u64) -> (u64, u64) {
+    ( us / 1_000_000, us % 1_000_000 * 1000 )
+}
+
+/// Returns the microseconds in `secs` seconds and `nanos` nanoseconds,
+/// rounded up, or `None` if `nanos` is not below `NANOS_PER_SEC` or the time
+/// does not fit in 64 bits.
+fn from_timespec(secs: u64, nanos: u64) -> Option<u64> {
+    if nanos >= NANOS_PER_SEC {
+        return None
+    }
+    secs.checked_mul( 1_000_000 )?.checked_add( ( nanos + 999 ) / 1000 )
+}
+
+/// Read clock `clock`.
+///
+/// This system call takes one parameter: `CLOCK_MONOTONIC` for the time since
+/// boot or `CLOCK_REALTIME` for the time since the Unix epoch. Fails with
+/// `InvalidArgument` for any other clock. Both clocks have a resolution of
+/// one microsecond.
+///
+/// In addition to the usual status value, this system call returns two
+/// parameters: the seconds in `x0` and the nanoseconds in `x1`.
+pub fn clock_gettime(clock: u64, tf: &mut TrapFrame) {
+    match clock::read( clock ) {
+        Some(us) => {
+            let ( secs, nanos ) = to_timespec( us );
+            tf.x0 = secs;
+            tf.x1_x29[0] = nanos;
+            ok( tf );
+        },
+        None => fail( tf, OsError::InvalidArgument ),
+    }
+}
+
+/// Sleep for `secs` seconds and `nanos` nanoseconds.
+///
+/// This system call takes two parameters: the seconds and the nanoseconds,
+/// below `NANOS_PER_SEC`. The time is rounded up to a whole microsecond. A
+/// signal interrupts the sleep with `Interrupted`.
+///
+/// In addition to the usual status value, this system call returns two
+/// parameters: the seconds in `x0` and the nanoseconds in `x1` that remained
+/// when the sleep was interrupted, both `0` if it was not.
+pub fn nanosleep(secs: u64, nanos: u64, tf: &mut TrapFrame) {
+    let us = match from_timespec( secs, nanos ) {
+        Some(x) => x,
+        None => {
+            fail( tf, OsError::InvalidArgument );
+            return
+        },
+    };
+    let t_end = clock::monotonic().saturating_add( us );
+
+    wait( nr::NANOSLEEP, move |p| {
+        let t_now = clock::monotonic();
+        if t_now >= t_end {
+            p.trap_frame.x0 = 0;
+            p.trap_frame.x1_x29[0] = 0;
+            ok( &mut p.trap_frame );
+            true
+        } else if p.signals.is_interrupted() {
+            let ( secs, nanos ) = to_timespec( t_end - t_now );
+            p.trap_frame.x0 = secs;
+            p.trap_frame.x1_x29[0] = nanos;
+            fail( &mut p.trap_frame, OsError::Interrupted );
+            true
+        } else {
+            false
+        }
+    }, tf );
+}
+
+/// Set the wall clock.
+///
+/// This system call takes two parameters: the seconds and the nanoseconds,
+/// below `NANOS_PER_SEC`, since the Unix epoch. `CLOCK_REALTIME` counts from
+/// this time on; `CLOCK_MONOTONIC` is not affected.
+///
+/// This system call returns no parameters.
+pub fn settime(secs: u64, nanos: u64, tf: &mut TrapFrame) {
+    match from_timespec( secs, nanos ) {
+        Some(us) => {
+            clock::set_realtime( us );
+            ok( tf );
+        },
+        None => fail( tf, OsError::InvalidArgument ),
+    }
+}
+
+/// The arguments of a system call, `x0` to `x5`.
+type Args = [u64; abi::NARGS];
+
+/// An entry of the system call table.
+struct Syscall {
+    /// The number the entry is stored under.
+    nr: u16,
+    /// The name of the call, used in diagnostics.
+    name: &'static str,
+    /// The number of arguments the call takes, printed when tracing.
+    nargs: usize,
+    /// Performs the call for the process executing in the trap frame. The
+    /// result and status are stored in the trap frame before the process can
+    /// be switched out, since the frame then belongs to another process.
+    handler: fn(Args, &mut TrapFrame),
+}
+
+/// The system calls, indexed by number.
+static SYSCALLS: [Option<Syscall>; nr::COUNT] = [
+    None,
+    Some( Syscall { nr: nr::SLEEP, name: "sleep", nargs: 1, handler: sys_sleep } ),
+    Some( Syscall { nr: nr::EXIT, name: "exit", nargs: 0, handler: sys_exit } ),
+    Some( Syscall { nr: nr::FUTEX_WAIT, name: "futex_wait", nargs: 2, handler: sys_futex_wait } ),
+    Some( Syscall { nr: nr::FUTEX_WAKE, name: "futex_wake", nargs: 2, handler: sys_futex_wake } ),
+    Some( Syscall { nr: nr::PIPE, name: "pipe", nargs: 0, handler: sys_pipe } ),
+    Some( Syscall { nr: nr::READ, name: "read", nargs: 3, handler: sys_read } ),
+    Some( Syscall { nr: nr::WRITE, name: "write", nargs: 3, handler: sys_write } ),
+    Some( Syscall { nr: nr::CLOSE, name: "close", nargs: 1, handler: sys_close } ),
+    Some( Syscall { nr: nr::SEND, name: "send", nargs: 2, handler: sys_send } ),
+    Some( Syscall { nr: nr::RECV, name: "recv", nargs: 2, handler: sys_recv } ),
+    Some( Syscall { nr: nr::SHM_CREATE, name: "shm_create", nargs: 3, handler: sys_shm_create } ),
+    Some( Syscall { nr: nr::SHM_MAP, name: "shm_map", nargs: 2, handler: sys_shm_map } ),
+    Some( Syscall { nr: nr::SHM_DROP, name: "shm_drop", nargs: 1, handler: sys_shm_drop } ),
+    Some( Syscall { nr: nr::KILL, name: "kill", nargs: 2, handler: sys_kill } ),
+    Some( Syscall { nr: nr::SIGACTION, name: "sigaction", nargs: 3, handler: sys_sigaction } ),
+    Some( Syscall { nr: nr::SIGPROCMASK, name: "sigprocmask", nargs: 2, handler: sys_sigprocmask } ),
+    Some( Syscall { nr: nr::SIGRETURN, name: "sigreturn", nargs: 0, handler: sys_sigreturn } ),
+    Some( Syscall { nr: nr::GETPID, name: "getpid", nargs: 0, handler: sys_getpid } ),
+    Some( Syscall { nr: nr::GETPPID, name: "getppid", nargs: 0, handler: sys_getppid } ),
+    Some( Syscall { nr: nr::GETRLIMIT, name: "getrlimit", nargs: 1, handler: sys_getrlimit } ),
+    Some( Syscall { nr: nr::SETRLIMIT, name: "setrlimit", nargs: 2, handler: sys_setrlimit } ),
+    Some( Syscall { nr: nr::SCHED_SETAFFINITY, name: "sched_setaffinity", nargs: 2, handler: sys_sched_setaffinity } ),
+    Some( Syscall { nr: nr::SCHED_GETAFFINITY, name: "sched_getaffinity", nargs: 1, handler: sys_sched_getaffinity } ),
+    Some( Syscall { nr: nr::MMAP, name: "mmap", nargs: 1, handler: sys_mmap } ),
+    Some( Syscall { nr: nr::MUNMAP, name: "munmap", nargs: 1, handler: sys_munmap } ),
+    Some( Syscall { nr: nr::CLOCK_GETTIME, name: "clock_gettime", nargs: 1, handler: sys_clock_gettime } ),
+    Some( Syscall { nr: nr::NANOSLEEP, name: "nanosleep", nargs: 2, handler: sys_nanosleep } ),
+    Some( Syscall { nr: nr::SETTIME, name: "settime", nargs: 2, handler: sys_settime } ),
+    Some( Syscall { nr: nr::YIELD, name: "yield", nargs: 0, handler: sys_yield } ),
+    Some( Syscall { nr: nr::SCHED_GETPARAM, name: "sched_getparam", nargs: 1, handler: sys_sched_getparam } ),
+    Some( Syscall { nr: nr::OPEN, name: "open", nargs: 2, handler: sys_open } ),
+    Some( Syscall { nr: nr::SEEK, name: "seek", nargs: 3, handler: sys_seek } ),
+    Some( Syscall { nr: nr::ARGS, name: "args", nargs: 2, handler: sys_args } ),
+];
+
+//adapters from the argument registers to the typed system calls
+fn sys_sleep(a: Args, tf: &mut TrapFrame) { sleep( a[0] as u32, tf ) }
+fn sys_exit(_: Args, tf: &mut TrapFrame) { exit( tf ) }
+fn sys_futex_wait(a: Args, tf: &mut TrapFrame) { futex_wait( a[0], a[1] as u32, tf ) }
+fn sys_futex_wake(a: Args, tf: &mut TrapFrame) { futex_wake( a[0], a[1] as usize, tf ) }
+fn sys_pipe(_: Args, tf: &mut TrapFrame) { pipe( tf ) }
+fn sys_read(a: Args, tf: &mut TrapFrame) { read( a[0], a[1], a[2], tf ) }
+fn sys_write(a: Args, tf: &mut TrapFrame) { write( a[0], a[1], a[2], tf ) }
+fn sys_close(a: Args, tf: &mut TrapFrame) { close( a[0], tf ) }
+fn sys_send(a: Args, tf: &mut TrapFrame) { send( a[0], a[1], tf ) }
+fn sys_recv(a: Args, tf: &mut TrapFrame) { recv( a[0], a[1], tf ) }
+fn sys_shm_create(a: Args, tf: &mut TrapFrame) { shm_create( a[0], a[1], a[2], tf ) }
+fn sys_shm_map(a: Args, tf: &mut TrapFrame) { shm_map( a[0], a[1], tf ) }
+fn sys_shm_drop(a: Args, tf: &mut TrapFrame) { shm_drop( a[0], tf ) }
+fn sys_kill(a: Args, tf: &mut TrapFrame) { kill( a[0], a[1] as Signal, tf ) }
+fn sys_sigaction(a: Args, tf: &mut TrapFrame) { sigaction( a[0] as Signal, a[1], a[2], tf ) }
+fn sys_sigprocmask(a: Args, tf: &mut TrapFrame) { sigprocmask( a[0], a[1] as u32, tf ) }
+fn sys_sigreturn(_: Args, tf: &mut TrapFrame) { sigreturn( tf ) }
+fn sys_getpid(_: Args, tf: &mut TrapFrame) { getpid( tf ) }
+fn sys_getppid(_: Args, tf: &mut TrapFrame) { getppid( tf ) }
+fn sys_getrlimit(a: Args, tf: &mut TrapFrame) { getrlimit( a[0], tf ) }
+fn sys_setrlimit(a: Args, tf: &mut TrapFrame) { setrlimit( a[0], a[1], tf ) }
+fn sys_sched_setaffinity(a: Args, tf: &mut TrapFrame) { sched_setaffinity( a[0], a[1], tf ) }
+fn sys_sched_getaffinity(a: Args, tf: &mut TrapFrame) { sched_getaffinity( a[0], tf ) }
+fn sys_mmap(a: Args, tf: &mut TrapFrame) { mmap( a[0], tf ) }
+fn sys_munmap(a: Args, tf: &mut TrapFrame) { munmap( a[0], tf ) }
+fn sys_clock_gettime(a: Args, tf: &mut TrapFrame) { clock_gettime( a[0], tf ) }
+fn sys_nanosleep(a: Args, tf: &mut TrapFrame) { nanosleep( a[0], a[1], tf ) }
+fn sys_settime(a: Args, tf: &mut TrapFrame) { settime( a[0], a[1], tf ) }
+fn sys_yield(_: Args, tf: &mut TrapFrame) { sched_yield( tf ) }
+fn sys_sched_getparam(a: Args, tf: &mut TrapFrame) { sched_getparam( a[0], tf ) }
+fn sys_open(a: Args, tf: &mut TrapFrame) { open( a[0], a[1], tf ) }
+fn sys_seek(a: Args, tf: &mut TrapFrame) { seek( a[0], a[1], a[2], tf ) }
+fn sys_args(a: Args, tf: &mut TrapFrame) { args( a[0], a[1], tf ) }
+
+/// Performs system call `num` for the process executing in `tf`, following
+/// the convention of the `abi` crate: arguments in `x0` to `x5`, the result in
+/// `x0` and the status in `x7`. Fails with `NoSuchSyscall` if there is no
+/// system call numbered `num`.
+///
+/// Calls made by a process with tracing enabled are logged to the console.
+/// Whether the running process is traced is cached per core, so other calls
+/// do not take the scheduler lock.
 pub fn handle_syscall(num: u16, tf: &mut TrapFrame) {
-    match num {
-        1 => { //sleep
-            let t_ms = tf.x0;
-            sleep( t_ms as u32, tf );
+    use console::kprintln;
+    use pi::timer;
+
+    let pid = tf.TPIDR;
+    let traced = process::is_traced();
+
+    let syscall = match SYSCALLS.get( num as usize ) {
+        Some(&Some(ref x)) => x,
+        _ => {
+            if traced {
+                kprintln!( "[{}] syscall {} = {}", pid, num, OsError::NoSuchSyscall );
+            }
+            return fail( tf, OsError::NoSuchSyscall )
         },
-        _ => {},
+    };
+    debug_assert_eq!( syscall.nr, num, "syscall table out of order at {}", syscall.name );
+
+    let args = syscall_args( tf );
+    let start = timer::current_time();
+    ( syscall.handler )( args, tf );
+
+    if traced {
+        kprintln!( "{}", describe( pid, syscall, &args, tf, timer::current_time() - start ) );
     }
 }
 
+/// Returns the arguments of the system call made in `tf`.
+fn syscall_args(tf: &TrapFrame) -> Args {
+    [ tf.x0, tf.x1_x29[0], tf.x1_x29[1], tf.x1_x29[2], tf.x1_x29[3], tf.x1_x29[4] ]
+}
+
+/// Returns the trace line of a call of `syscall` with `args` by process `pid`
+/// that took `us` microseconds. If the process was switched out, `tf` belongs
+/// to another process and the call is described as blocked: a call that is
+/// restarted is logged again, and one completed by `wait` is logged with its
+/// result once it completes.
+fn describe(pid: u64, syscall: &Syscall, args: &Args, tf: &TrapFrame, us: u64) -> String {
+    use std::fmt::Write;
+
+    let mut line = String::new();
+    let _ = write!( line, "[{}] {}(", pid, syscall.name );
+    for ( i, a ) in args[..syscall.nargs].iter().enumerate() {
+        let _ = write!( line, "{}{:#x}", if i == 0 { "" } else { ", " }, a );
+    }
+    line.push_str( ")" );
+
+    let _ = if tf.TPIDR != pid {
+        write!( line, " blocked ({} us)", us )
+    } else {
+        match abi::result( tf.x1_x29[6] ) {
+            Ok(()) => write!( line, " = {:#x} ({} us)", tf.x0, us ),
+            Err(e) => write!( line, " = {} ({} us)", e, us ),
+        }
+    };
+    line
+}
@@ -0,0 +1,275 @@
+use traps::{Source, Kind};
+use traps::policy::{self, Action, Class};
+use traps::syndrome::{Syndrome, Fault};
+
+const SOURCES: [Source; 4] = [Source::CurrentSpEl0, Source::CurrentSpElx,
+                              Source::LowerAArch64, Source::LowerAArch32];
+
+fn data_abort() -> Syndrome {
+    Syndrome::DataAbort { kind: Fault::Translation, level: 0 }
+}
+
+#[test]
+fn classes() {
+    assert_eq!(Class::of(Syndrome::Svc(3)), Class::Svc);
+    assert_eq!(Class::of(Syndrome::Brk(1)), Class::Brk);
+    assert_eq!(Class::of(data_abort()), Class::Abort);
+    assert_eq!(Class::of(Syndrome::SpAlignmentFault), Class::Abort);
+    assert_eq!(Class::of(Syndrome::Unknown), Class::Other);
+}
+
+#[test]
+fn syscalls() {
+    assert_eq!(policy::action(Source::LowerAArch64, Kind::Synchronous, Syndrome::Svc(1)), Action::Syscall);
+    assert_eq!(policy::action(Source::CurrentSpEl0, Kind::Synchronous, Syndrome::Svc(1)), Action::Syscall);
+    assert_eq!(policy::action(Source::CurrentSpElx, Kind::Synchronous, Syndrome::Svc(1)), Action::Panic);
+    assert_eq!(policy::action(Source::LowerAArch32, Kind::Synchronous, Syndrome::Svc(1)), Action::Kill);
+}
+
+#[test]
+fn interrupts_and_breakpoints_from_anywhere() {
+    for &source in SOURCES.iter() {
+        assert_eq!(policy::action(source, Kind::Irq, Syndrome::Unknown), Action::Irq);
+        assert_eq!(policy::action(source, Kind::Synchronous, Syndrome::Brk(2)), Action::Debug);
+    }
+}
+
+#[test]
+fn user_faults_kill() {
+    for &source in [Source::LowerAArch64, Source::LowerAArch32].iter() {
+        assert_eq!(policy::action(source, Kind::Synchronous, data_abort()), Action::Kill);
+        assert_eq!(policy::action(source, Kind::Synchronous, Syndrome::Unknown), Action::Kill);
+        assert_eq!(policy::action(source, Kind::SError, Syndrome::Unknown), Action::Kill);
+        assert_eq!(policy::action(source, Kind::Fiq, Syndrome::Unknown), Action::Report);
+    }
+}
+
+#[test]
+fn kernel_faults_panic() {
+    for &source in [Source::CurrentSpEl0, Source::CurrentSpElx].iter() {
+        assert_eq!(policy::action(source, Kind::Synchronous, data_abort()), Action::Panic);
+        assert_eq!(policy::action(source, Kind::SError, Syndrome::Unknown), Action::Panic);
+        assert_eq!(policy::action(source, Kind::Fiq, Syndrome::Unknown), Action::Panic);
+    }
+}
+
+#[test]
+fn syndrome_is_ignored_for_asynchronous_exceptions() {
+    assert_eq!(policy::action(Source::CurrentSpElx, Kind::Irq, Syndrome::Svc(1)), Action::Irq);
+    assert_eq!(policy::action(Source::LowerAArch64, Kind::SError, Syndrome::Svc(1)), Action::Kill);
+}
+
+/// System calls run against the simulated scheduler, with buffers on the
+/// stacks of the simulated processes.
+mod ipc {
+    use std::sync::Arc;
+
+    use abi::{self, OsError, MSG_SIZE, NO_TIMEOUT};
+    use ipc::MAILBOX_SIZE;
+    use process::{Id, UserSlice};
+    use process::signal::{Action, SIGUSR1};
+    use sync::Sched;
+    use sync::sim::*;
+    use traps::syscall;
+    use traps::TrapFrame;
+
+    /// Copies `bytes` into the stack of process `id`, `at` bytes below its
+    /// top, and returns their address.
+    fn put(id: Id, at: u64, bytes: &[u8]) -> u64 {
+        SimSched::with_process(id, |p| {
+            let addr = p.stack.top().as_u64() - at;
+            UserSlice::new(addr, bytes.len() as u64).copy_to_user(p, bytes).unwrap();
+            addr
+        }).unwrap()
+    }
+
+    /// Returns the result of the last system call of waiting process `id`.
+    fn result_of(id: Id) -> Result<u64, OsError> {
+        SimSched::with_process(id, |p| result(&p.trap_frame)).unwrap()
+    }
+
+    fn result(tf: &TrapFrame) -> Result<u64, OsError> {
+        abi::result(tf.x1_x29[6]).map(|()| tf.x0)
+    }
+
+    fn recv(id: Id, timeout_ms: u64) -> TrapFrame {
+        let buf = put(id, 128, &[0; MSG_SIZE]);
+        let mut t = tf(id);
+        syscall::recv(buf, timeout_ms, &mut t);
+        t
+    }
+
+    fn send(from: Id, to: Id, data: &[u8; MSG_SIZE]) -> Result<u64, OsError> {
+        let msg = put(from, 64, data);
+        let mut t = tf(from);
+        syscall::send(to, msg, &mut t);
+        result(&t)
+    }
+
+    #[test]
+    fn recv_without_timeout_would_block() {
+        spawn(1);
+        let t = recv(1, 0);
+        assert_eq!(result(&t), Err(OsError::WouldBlock));
+        assert!(!is_waiting(1));
+    }
+
+    #[test]
+    fn recv_times_out() {
+        spawn(1);
+        let t = recv(1, 5);
+        assert!(is_waiting(1));
+        assert_eq!(t.TPIDR, 0);
+
+        advance(4_000);
+        poll();
+        assert!(is_waiting(1));
+
+        advance(1_000);
+        poll();
+        assert!(!is_waiting(1));
+        assert_eq!(ready_queue(), vec![1]);
+        assert_eq!(result_of(1), Err(OsError::TimedOut));
+    }
+
+    #[test]
+    fn recv_is_interrupted_by_a_signal() {
+        spawn(1);
+        recv(1, NO_TIMEOUT);
+        poll();
+        assert!(is_waiting(1));
+
+        SimSched::with_process(1, |p| {
+            p.signals.set_action(SIGUSR1, Action::Handler { entry: 0x1000, restorer: 0x2000 });
+            p.signals.raise(SIGUSR1);
+        });
+        poll();
+        assert!(!is_waiting(1));
+        assert_eq!(result_of(1), Err(OsError::Interrupted));
+    }
+
+    #[test]
+    fn recv_takes_a_message_sent_while_waiting() {
+        spawn(1);
+        spawn(2);
+        recv(1, NO_TIMEOUT);
+
+        assert_eq!(send(2, 1, &[7; MSG_SIZE]), Ok(0));
+        poll();
+        assert_eq!(result_of(1), Ok(2));
+        let data = SimSched::with_process(1, |p| {
+            let buf = p.stack.top().as_u64() - 128;
+            UserSlice::new(buf, MSG_SIZE as u64).to_vec(p).unwrap()
+        }).unwrap();
+        assert_eq!(data, vec![7; MSG_SIZE]);
+    }
+
+    #[test]
+    fn send_to_a_full_mailbox_would_block() {
+        spawn(1);
+        spawn(2);
+        for _ in 0..MAILBOX_SIZE {
+            assert_eq!(send(2, 1, &[1; MSG_SIZE]), Ok(0));
+        }
+        assert_eq!(send(2, 1, &[1; MSG_SIZE]), Err(OsError::WouldBlock));
+
+        let t = recv(1, 0);
+        assert_eq!(result(&t), Ok(2));
+        assert_eq!(send(2, 1, &[1; MSG_SIZE]), Ok(0));
+    }
+
+    #[test]
+    fn send_to_no_process() {
+        spawn(2);
+        assert_eq!(send(2, 9, &[1; MSG_SIZE]), Err(OsError::NoSuchProcess));
+    }
+
+    fn shm_create(id: Id, name: &str, size: u64) -> Result<u64, OsError> {
+        let addr = put(id, 64, name.as_bytes());
+        let mut t = tf(id);
+        syscall::shm_create(addr, name.len() as u64, size, &mut t);
+        result(&t)
+    }
+
+    fn shm_map(id: Id, name: &str) -> Result<u64, OsError> {
+        let addr = put(id, 64, name.as_bytes());
+        let mut t = tf(id);
+        syscall::shm_map(addr, name.len() as u64, &mut t);
+        result(&t)
+    }
+
+    fn shm_drop(id: Id, addr: u64) -> Result<u64, OsError> {
+        let mut t = tf(id);
+        syscall::shm_drop(addr, &mut t);
+        result(&t)
+    }
+
+    /// Returns the number of mappings of the region at `addr`.
+    fn mappings(id: Id, addr: u64) -> usize {
+        SimSched::with_process(id, |p| {
+            let r = p.regions.iter().find(|r| r.start().as_u64() == addr).unwrap();
+            Arc::strong_count(r)
+        }).unwrap()
+    }
+
+    #[test]
+    fn shm_mappings_are_counted() {
+        spawn(1);
+        spawn(2);
+        let addr = shm_create(1, "counted", 100).unwrap();
+        assert_eq!(mappings(1, addr), 1);
+        assert_eq!(shm_create(2, "counted", 100), Err(OsError::AlreadyExists));
+
+        assert_eq!(shm_map(2, "counted"), Ok(addr));
+        assert_eq!(shm_map(2, "counted"), Ok(addr));
+        assert_eq!(mappings(1, addr), 3);
+
+        assert_eq!(shm_drop(2, addr), Ok(0));
+        assert_eq!(shm_drop(1, addr), Ok(0));
+        assert_eq!(mappings(2, addr), 1);
+        assert_eq!(shm_drop(1, addr), Err(OsError::InvalidArgument));
+        assert_eq!(shm_map(1, "counted"), Ok(addr));
+    }
+
+    #[test]
+    fn shm_is_released_with_the_last_mapping() {
+        spawn(1);
+        spawn(2);
+        let addr = shm_create(1, "released", 100).unwrap();
+        assert_eq!(shm_map(2, "released"), Ok(addr));
+
+        assert_eq!(shm_drop(1, addr), Ok(0));
+        assert_eq!(shm_drop(2, addr), Ok(0));
+        assert_eq!(shm_map(1, "released"), Err(OsError::NotFound));
+
+        let again = shm_create(2, "released", 100).unwrap();
+        assert_eq!(mappings(2, again), 1);
+    }
+
+    #[test]
+    fn sleep_returns_the_elapsed_time_once_it_ends() {
+        spawn(1);
+        let mut t = tf(1);
+        syscall::sleep(5, &mut t);
+        assert!(is_waiting(1));
+
+        advance(4_999);
+        poll();
+        assert!(is_waiting(1));
+
+        advance(2_001);
+        poll();
+        assert!(!is_waiting(1));
+        assert_eq!(result_of(1), Ok(7));
+    }
+
+    #[test]
+    fn kill_rejects_kernel_threads() {
+        spawn(1);
+        spawn(2);
+        SimSched::with_process(2, |p| p.trap_frame.SPSR = 0b0100);
+        let mut t = tf(1);
+        syscall::kill(2, SIGUSR1, &mut t);
+        assert_eq!(result(&t), Err(OsError::PermissionDenied));
+    }
+}
@@ -4,31 +4,80 @@ use volatile::{Volatile, ReadVolatile};
 
 const INT_BASE: usize = IO_BASE + 0xB000 + 0x200;
 
-#[derive(Copy, Clone, PartialEq)]
+/// One more than the highest interrupt number.
+pub const NIRQ: usize = 72;
+
+/// An interrupt source. Interrupts `0` to `63` are the GPU peripheral
+/// interrupts of pending registers 1 and 2; interrupts from `64` are the ARM
+/// interrupts of the basic pending register.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Interrupt {
     Timer1 = 1,
     Timer3 = 3,
     Usb = 9,
+    Aux = 29,
     Gpio0 = 49,
     Gpio1 = 50,
     Gpio2 = 51,
     Gpio3 = 52,
     Uart = 57,
+    ArmTimer = 64,
+    ArmMailbox = 65,
+    ArmDoorbell0 = 66,
+    ArmDoorbell1 = 67,
+    GpuHalted0 = 68,
+    GpuHalted1 = 69,
+    IllegalAccess1 = 70,
+    IllegalAccess0 = 71,
+}
+
+impl Interrupt {
+    /// Every interrupt source.
+    pub const ALL: [Interrupt; 17] = [
+        Interrupt::Timer1, Interrupt::Timer3, Interrupt::Usb, Interrupt::Aux,
+        Interrupt::Gpio0, Interrupt::Gpio1, Interrupt::Gpio2, Interrupt::Gpio3,
+        Interrupt::Uart,
+        Interrupt::ArmTimer, Interrupt::ArmMailbox,
+        Interrupt::ArmDoorbell0, Interrupt::ArmDoorbell1,
+        Interrupt::GpuHalted0, Interrupt::GpuHalted1,
+        Interrupt::IllegalAccess1, Interrupt::IllegalAccess0,
+    ];
+
+    /// Returns the interrupt number of `self`.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the bank of registers of `self` and its bit in them.
+    fn bank(self) -> (Bank, u32) {
+        match self as u32 {
+            x @ 0...31 => (Bank::Gpu1, 1 << x),
+            x @ 32...63 => (Bank::Gpu2, 1 << (x - 32)),
+            x => (Bank::Basic, 1 << (x - 64)),
+        }
+    }
+}
+
+/// The registers an interrupt is enabled, disabled and pending in.
+enum Bank {
+    Gpu1,
+    Gpu2,
+    Basic,
 }
 
 #[repr(C)]
 #[allow(non_snake_case)]
 struct Registers {
-    pub IRQ_Basic_Pending: u32,
-    pub IRQ_Pending_1: u32,
-    pub IRQ_Pending_2: u32,
-    pub FIQ_Control: u32,
-    pub Enable_IRQ_1: u32,
-    pub Enable_IRQ_2: u32,
-    pub Enable_Basic_IRQs: u32,
-    pub Disable_IRQ_1: u32,
-    pub Disable_IRQ_2: u32,
-    pub Disable_Basic_IRQs: u32,
+    pub IRQ_Basic_Pending: ReadVolatile<u32>,
+    pub IRQ_Pending_1: ReadVolatile<u32>,
+    pub IRQ_Pending_2: ReadVolatile<u32>,
+    pub FIQ_Control: Volatile<u32>,
+    pub Enable_IRQ_1: Volatile<u32>,
+    pub Enable_IRQ_2: Volatile<u32>,
+    pub Enable_Basic_IRQs: Volatile<u32>,
+    pub Disable_IRQ_1: Volatile<u32>,
+    pub Disable_IRQ_2: Volatile<u32>,
+    pub Disable_Basic_IRQs: Volatile<u32>,
 }
 
 /// An interrupt controller. Used to enable and disable interrupts as well as to
@@ -48,31 +97,30 @@ impl Controller {
 
     /// Enables the interrupt `int`.
     pub fn enable(&mut self, int: Interrupt) {
-        if int as u32 >= 32 {
-            let adjust = int as u32 - 32;
-            self.registers.Enable_IRQ_2 |= 1 << adjust;
-        } else {
-            self.registers.Enable_IRQ_1 |= 1 << int as u32;
+        //writing a 1 bit enables the interrupt, 0 bits have no effect
+        match int.bank() {
+            (Bank::Gpu1, bit) => self.registers.Enable_IRQ_1.write( bit ),
+            (Bank::Gpu2, bit) => self.registers.Enable_IRQ_2.write( bit ),
+            (Bank::Basic, bit) => self.registers.Enable_Basic_IRQs.write( bit ),
         }
     }
 
     /// Disables the interrupt `int`.
     pub fn disable(&mut self, int: Interrupt) {
-        if int as u32 >= 32 {
-            let adjust = int as u32 - 32;
-            self.registers.Disable_IRQ_2 |= 1 << adjust;
-        } else {
-            self.registers.Disable_IRQ_1 |= 1 << int as u32;
-        }        
+        //writing a 1 bit disables the interrupt, 0 bits have no effect
+        match int.bank() {
+            (Bank::Gpu1, bit) => self.registers.Disable_IRQ_1.write( bit ),
+            (Bank::Gpu2, bit) => self.registers.Disable_IRQ_2.write( bit ),
+            (Bank::Basic, bit) => self.registers.Disable_Basic_IRQs.write( bit ),
+        }
     }
 
     /// Returns `true` if `int` is pending. Otherwise, returns `false`.
     pub fn is_pending(&self, int: Interrupt) -> bool {
-        if int as u32 >= 32 {
-            let adjust = int as u32 - 32;
-            self.registers.IRQ_Pending_2 & ( 1 << adjust ) != 0
-        } else {
-            self.registers.IRQ_Pending_1 & ( 1 << int as u32 ) != 0
+        match int.bank() {
+            (Bank::Gpu1, bit) => self.registers.IRQ_Pending_1.has_mask( bit ),
+            (Bank::Gpu2, bit) => self.registers.IRQ_Pending_2.has_mask( bit ),
+            (Bank::Basic, bit) => self.registers.IRQ_Basic_Pending.has_mask( bit ),
         }
     }
 }
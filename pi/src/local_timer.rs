@@ -0,0 +1,44 @@
+use volatile::prelude::*;
+use volatile::{Volatile, ReadVolatile};
+
+/// The base address of the ARM local peripherals (QA7 rev 3.4).
+const LOCAL_BASE: usize = 0x4000_0000;
+
+/// The per-core "Core timers interrupt control" registers.
+const CORE_TIMER_IRQCNTL: *mut Volatile<u32> = (LOCAL_BASE + 0x40) as *mut Volatile<u32>;
+
+/// The per-core "Core IRQ Source" registers.
+const CORE_IRQ_SOURCE: *const ReadVolatile<u32> = (LOCAL_BASE + 0x60) as *const ReadVolatile<u32>;
+
+/// Bit for the physical non-secure generic timer (CNTPNSIRQ) in both the
+/// interrupt control and interrupt source registers.
+const CNTPNSIRQ: u32 = 1 << 1;
+
+/// Reads the generic timer frequency in Hz.
+fn frequency() -> u64 {
+    let f: u64;
+    unsafe { asm!("mrs $0, CNTFRQ_EL0" : "=r"(f)); }
+    f
+}
+
+/// Routes the physical generic timer interrupt of core `core` to its IRQ
+/// line.
+pub fn enable(core: usize) {
+    unsafe { (*CORE_TIMER_IRQCNTL.add(core)).or_mask( CNTPNSIRQ ); }
+}
+
+/// Returns `true` if the generic timer interrupt of core `core` is pending.
+pub fn is_pending(core: usize) -> bool {
+    unsafe { (*CORE_IRQ_SOURCE.add(core)).read() & CNTPNSIRQ != 0 }
+}
+
+/// Sets up the generic timer of the calling core to fire in `us`
+/// microseconds. Writing the timer value also clears a pending interrupt.
+pub fn tick_in(us: u32) {
+    let ticks = frequency() * us as u64 / 1_000_000;
+    unsafe {
+        asm!("msr CNTP_TVAL_EL0, $0
+              msr CNTP_CTL_EL0, $1"
+             :: "r"(ticks), "r"(1u64) :: "volatile"); //enabled, not masked
+    }
+}
@@ -20,6 +20,11 @@ enum LsrStatus {
     TxAvailable = 1 << 5,
 }
 
+/// Value of the `AUX_MU_IER_REG` register enabling the receive interrupt. Per
+/// the BCM2835 datasheet errata, bit 0, not bit 1, enables receive interrupts
+/// and the reserved bits 2 and 3 must be set for them to be raised.
+const IER_RX_ENABLE: u32 = 0b1101;
+
 #[repr(C)]
 #[allow(non_snake_case)]
 struct Registers {
@@ -79,6 +84,13 @@ impl MiniUart {
         }
     }
 
+    /// Enables or disables the receive interrupt. While enabled, the mini UART
+    /// raises `Interrupt::Aux` as long as there is at least one byte to read.
+    pub fn set_rx_interrupt(&mut self, enable: bool) {
+        let ier = if enable { IER_RX_ENABLE } else { 0 };
+        (*self.registers).IER.write( ier );
+    }
+
     /// Set the read timeout to `milliseconds` milliseconds.
     pub fn set_read_timeout(&mut self, milliseconds: u32) {
         self.timeout = Some( milliseconds );
@@ -367,6 +367,9 @@ extern crate core as __core;
 extern crate alloc;
 //- extern crate alloc_system;
 extern crate std_unicode;
+// system call numbers, errors and the `svc` wrappers shared with the kernel
+#[macro_use]
+extern crate abi;
 //- #[doc(masked)]
 //- extern crate libc;
 
@@ -475,12 +478,15 @@ pub use core::u128;
 
 //- #[macro_use]
 //- pub mod thread;
+#[path = "ros/thread.rs"] pub mod thread; //- Added.
 pub mod ascii;
 pub mod collections;
 //- pub mod env;
+#[path = "ros/env.rs"] pub mod env; //- Added.
 pub mod error;
 pub mod ffi;
 //- pub mod fs;
+#[path = "ros/fs.rs"] pub mod fs; //- Added.
 pub mod io;
 //- pub mod net;
 pub mod num;
@@ -488,8 +494,10 @@ pub mod os;
 //- pub mod panic;
 pub mod path;
 //- pub mod process;
+#[path = "ros/process.rs"] pub mod process; //- Added.
 pub mod sync;
 //- pub mod time;
+pub mod time; //- Added.
 //- pub mod heap;
 
 //- // Platform-abstraction modules
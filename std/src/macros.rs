@@ -119,6 +119,12 @@
 //- macro_rules! print {
 //-     ($($arg:tt)*) => ($crate::io::_print(format_args!($($arg)*)));
 //- }
+#[macro_export]
+#[stable(feature = "rust1", since = "1.0.0")]
+#[allow_internal_unstable]
+macro_rules! print { //- Added.
+    ($($arg:tt)*) => ($crate::io::_print(format_args!($($arg)*)));
+}
 
 /// Macro for printing to the standard output, with a newline.
 ///
@@ -152,6 +158,13 @@
 //-     ($fmt:expr) => (print!(concat!($fmt, "\n")));
 //-     ($fmt:expr, $($arg:tt)*) => (print!(concat!($fmt, "\n"), $($arg)*));
 //- }
+#[macro_export]
+#[stable(feature = "rust1", since = "1.0.0")]
+macro_rules! println { //- Added.
+    () => (print!("\n"));
+    ($fmt:expr) => (print!(concat!($fmt, "\n")));
+    ($fmt:expr, $($arg:tt)*) => (print!(concat!($fmt, "\n"), $($arg)*));
+}
 
 /// Macro for printing to the standard error.
 ///
@@ -180,6 +193,12 @@
 //- macro_rules! eprint {
 //-     ($($arg:tt)*) => ($crate::io::_eprint(format_args!($($arg)*)));
 //- }
+#[macro_export]
+#[stable(feature = "eprint", since = "1.19.0")]
+#[allow_internal_unstable]
+macro_rules! eprint { //- Added.
+    ($($arg:tt)*) => ($crate::io::_eprint(format_args!($($arg)*)));
+}
 
 /// Macro for printing to the standard error, with a newline.
 ///
@@ -209,6 +228,13 @@
 //-     ($fmt:expr) => (eprint!(concat!($fmt, "\n")));
 //-     ($fmt:expr, $($arg:tt)*) => (eprint!(concat!($fmt, "\n"), $($arg)*));
 //- }
+#[macro_export]
+#[stable(feature = "eprint", since = "1.19.0")]
+macro_rules! eprintln { //- Added.
+    () => (eprint!("\n"));
+    ($fmt:expr) => (eprint!(concat!($fmt, "\n")));
+    ($fmt:expr, $($arg:tt)*) => (eprint!(concat!($fmt, "\n"), $($arg)*));
+}
 
 /// A macro to select an event from a number of receivers.
 ///
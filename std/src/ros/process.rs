@@ -0,0 +1,67 @@
+//! A module for working with processes.
+//!
+//! This is the part of upstream `std::process` the `ros` backend supports:
+//! terminating the current process and reading its ID.
+
+#![stable(feature = "process", since = "1.0.0")]
+
+use sys::syscall;
+
+/// Terminates the current process with the specified exit code.
+///
+/// This function will never return and will immediately terminate the current
+/// process.
+///
+/// Note that because this function never returns, and that it terminates the
+/// process, no destructors on the current stack will be run. If a clean
+/// shutdown is needed it is recommended to only call this function at a known
+/// point where there are no more destructors left to run.
+///
+/// ## Platform-specific behavior
+///
+/// The kernel does not keep exit codes yet, so `code` is not visible to any
+/// other process.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::process;
+///
+/// process::exit(0);
+/// ```
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn exit(_code: i32) -> ! {
+    syscall::exit()
+}
+
+/// Terminates the process in an abnormal fashion.
+///
+/// The function will never return and will immediately terminate the current
+/// process. No destructors on the current stack will be run.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::process;
+///
+/// process::abort();
+/// ```
+#[stable(feature = "process_abort", since = "1.17.0")]
+pub fn abort() -> ! {
+    syscall::exit()
+}
+
+/// Returns the OS-assigned process identifier associated with this process.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(getpid)]
+/// use std::process;
+///
+/// println!("My pid is {}", process::id());
+/// ```
+#[unstable(feature = "getpid", issue = "44971", reason = "recently added")]
+pub fn id() -> u32 {
+    syscall::getpid() as u32
+}
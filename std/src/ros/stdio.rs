@@ -0,0 +1,133 @@
+//! Standard input, output and error.
+//!
+//! This is the part of upstream `std::io::stdio` the `ros` backend supports:
+//! unbuffered handles to the console, open on file descriptors `0` to `2` of
+//! every user program, and the functions behind `print!` and `eprint!`.
+
+use fmt;
+use io::{self, Read, Write};
+use sys::stdio;
+
+/// A handle to the standard input stream of a process, read from the console.
+///
+/// Created by the [`io::stdin`] method.
+///
+/// [`io::stdin`]: fn.stdin.html
+#[stable(feature = "rust1", since = "1.0.0")]
+pub struct Stdin {
+    inner: stdio::Stdin,
+}
+
+/// A handle to the standard output stream of a process, written to the
+/// console.
+///
+/// Created by the [`io::stdout`] method.
+///
+/// [`io::stdout`]: fn.stdout.html
+#[stable(feature = "rust1", since = "1.0.0")]
+pub struct Stdout {
+    inner: stdio::Stdout,
+}
+
+/// A handle to the standard error stream of a process, written to the
+/// console.
+///
+/// Created by the [`io::stderr`] method.
+///
+/// [`io::stderr`]: fn.stderr.html
+#[stable(feature = "rust1", since = "1.0.0")]
+pub struct Stderr {
+    inner: stdio::Stderr,
+}
+
+/// Constructs a new handle to the standard input of the current process.
+///
+/// Reading waits until at least one byte has been received by the console.
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn stdin() -> Stdin {
+    Stdin { inner: stdio::Stdin::new().expect("stdin") }
+}
+
+/// Constructs a new handle to the standard output of the current process.
+///
+/// Output is not buffered: every write is a system call.
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn stdout() -> Stdout {
+    Stdout { inner: stdio::Stdout::new().expect("stdout") }
+}
+
+/// Constructs a new handle to the standard error of the current process.
+#[stable(feature = "rust1", since = "1.0.0")]
+pub fn stderr() -> Stderr {
+    Stderr { inner: stdio::Stderr::new().expect("stderr") }
+}
+
+#[stable(feature = "std_debug", since = "1.16.0")]
+impl fmt::Debug for Stdin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Stdin { .. }")
+    }
+}
+
+#[stable(feature = "std_debug", since = "1.16.0")]
+impl fmt::Debug for Stdout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Stdout { .. }")
+    }
+}
+
+#[stable(feature = "std_debug", since = "1.16.0")]
+impl fmt::Debug for Stderr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Stderr { .. }")
+    }
+}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Read for Stdin {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[unstable(feature = "print_internals",
+           reason = "implementation detail which may disappear or be replaced at any time",
+           issue = "0")]
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments) {
+    if let Err(e) = stdout().write_fmt(args) {
+        panic!("failed printing to stdout: {}", e);
+    }
+}
+
+#[unstable(feature = "print_internals",
+           reason = "implementation detail which may disappear or be replaced at any time",
+           issue = "0")]
+#[doc(hidden)]
+pub fn _eprint(args: fmt::Arguments) {
+    if let Err(e) = stderr().write_fmt(args) {
+        panic!("failed printing to stderr: {}", e);
+    }
+}
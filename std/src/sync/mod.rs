@@ -44,16 +44,18 @@ pub use core::sync::atomic;
 //- mod rwlock;
 
 //- EVERYTHING BELOW HERE WAS ADDED
-use sync::atomic::{AtomicBool, Ordering};
 use cell::UnsafeCell;
 use ops::{DerefMut, Deref, Drop};
 use fmt;
+use sys::mutex as imp;
+use sys::condvar as cv_imp;
 
-#[repr(align(32))]
+/// A mutual exclusion lock for user processes. A contended `lock` sleeps in
+/// the kernel through the `futex_wait` system call instead of spinning.
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct Mutex<T> {
+    inner: imp::Mutex,
     data: UnsafeCell<T>,
-    lock: AtomicBool,
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -77,41 +79,30 @@ impl<T> Mutex<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub const fn new(val: T) -> Mutex<T> {
         Mutex {
-            lock: AtomicBool::new(false),
+            inner: imp::Mutex::new(),
             data: UnsafeCell::new(val)
         }
     }
 }
 
 impl<T> Mutex<T> {
-    // Once MMU/cache is enabled, do the right thing here. For now, we don't
-    // need any real synchronization.
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn try_lock(&self) -> Option<MutexGuard<T>> {
-        if !self.lock.load(Ordering::Relaxed) {
-            self.lock.store(true, Ordering::Relaxed);
+        if unsafe { self.inner.try_lock() } {
             Some(MutexGuard { lock: &self })
         } else {
             None
         }
     }
 
-    // Once MMU/cache is enabled, do the right thing here. For now, we don't
-    // need any real synchronization.
-    #[inline(never)]
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn lock(&self) -> Result<MutexGuard<T>, !> {
-        // Wait until we can "aquire" the lock, then "acquire" it.
-        loop {
-            match self.try_lock() {
-                Some(guard) => return Ok(guard),
-                None => continue
-            }
-        }
+        unsafe { self.inner.lock(); }
+        Ok(MutexGuard { lock: &self })
     }
 
     fn unlock(&self) {
-        self.lock.store(false, Ordering::Relaxed);
+        unsafe { self.inner.unlock(); }
     }
 }
 
@@ -147,3 +138,43 @@ impl<T: fmt::Debug> fmt::Debug for Mutex<T> {
         }
     }
 }
+
+/// A condition variable for user processes, sleeping in the kernel through
+/// the `futex_wait` system call. Spurious wakeups are possible, so the
+/// condition must be checked again after `wait` returns.
+#[stable(feature = "rust1", since = "1.0.0")]
+pub struct Condvar {
+    inner: cv_imp::Condvar,
+}
+
+impl Condvar {
+    #[stable(feature = "rust1", since = "1.0.0")]
+    pub const fn new() -> Condvar {
+        Condvar { inner: cv_imp::Condvar::new() }
+    }
+
+    /// Releases the lock of `guard`, sleeps until notified and acquires the
+    /// lock again.
+    #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> Result<MutexGuard<'a, T>, !> {
+        unsafe { self.inner.wait(&guard.lock.inner); }
+        Ok(guard)
+    }
+
+    #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn notify_one(&self) {
+        self.inner.notify_one()
+    }
+
+    #[stable(feature = "rust1", since = "1.0.0")]
+    pub fn notify_all(&self) {
+        self.inner.notify_all()
+    }
+}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+impl fmt::Debug for Condvar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad("Condvar { .. }")
+    }
+}
@@ -0,0 +1,53 @@
+use abi;
+use ffi::OsString;
+use marker::PhantomData;
+use vec;
+use sys::os_str::Buf;
+use sys::syscall;
+use sys_common::FromInner;
+
+/// The arguments of the process, read from the kernel.
+pub struct Args {
+    iter: vec::IntoIter<OsString>,
+    _dont_send_or_sync_me: PhantomData<*mut ()>,
+}
+
+/// Returns the arguments of the process, starting with its name.
+pub fn args() -> Args {
+    //the arguments are only copied if the buffer can hold all of them
+    let mut buf = Vec::new();
+    loop {
+        let size = syscall::args(&mut buf).unwrap_or(0);
+        if size <= buf.len() {
+            buf.truncate(size);
+            break
+        }
+        buf.resize(size, 0);
+    }
+
+    let args: Vec<OsString> = abi::args::split(&buf)
+        .map(|arg| OsString::from_inner(Buf { inner: arg.to_vec() }))
+        .collect();
+
+    Args { iter: args.into_iter(), _dont_send_or_sync_me: PhantomData }
+}
+
+impl Args {
+    pub fn inner_debug(&self) -> &[OsString] {
+        self.iter.as_slice()
+    }
+}
+
+impl Iterator for Args {
+    type Item = OsString;
+    fn next(&mut self) -> Option<OsString> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+impl ExactSizeIterator for Args {
+    fn len(&self) -> usize { self.iter.len() }
+}
+
+impl DoubleEndedIterator for Args {
+    fn next_back(&mut self) -> Option<OsString> { self.iter.next_back() }
+}
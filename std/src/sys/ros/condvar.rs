@@ -0,0 +1,49 @@
+use cell::UnsafeCell;
+use intrinsics::{atomic_load, atomic_xadd};
+
+use sys::mutex::Mutex;
+use sys::syscall::{futex_wait, futex_wake};
+
+/// A condition variable that sleeps in the kernel.
+///
+/// Every notification bumps a sequence number. A waiter reads it before
+/// releasing the mutex and sleeps only while it is unchanged, so a
+/// notification between the unlock and the `futex_wait` is not lost.
+pub struct Condvar {
+    seq: UnsafeCell<u32>,
+}
+
+unsafe impl Send for Condvar {}
+unsafe impl Sync for Condvar {}
+
+impl Condvar {
+    pub const fn new() -> Condvar {
+        Condvar { seq: UnsafeCell::new(0) }
+    }
+
+    #[inline]
+    pub fn notify_one(&self) {
+        unsafe {
+            atomic_xadd(self.seq.get(), 1);
+            let _ = futex_wake(self.seq.get(), 1);
+        }
+    }
+
+    #[inline]
+    pub fn notify_all(&self) {
+        unsafe {
+            atomic_xadd(self.seq.get(), 1);
+            let _ = futex_wake(self.seq.get(), usize::max_value());
+        }
+    }
+
+    /// Releases `mutex`, sleeps until notified and acquires `mutex` again.
+    /// Spurious wakeups are possible.
+    #[inline]
+    pub unsafe fn wait(&self, mutex: &Mutex) {
+        let seq = atomic_load(self.seq.get());
+        mutex.unlock();
+        let _ = futex_wait(self.seq.get(), seq);
+        mutex.lock();
+    }
+}
@@ -0,0 +1,43 @@
+use io::{self, SeekFrom};
+use path::Path;
+use abi::OsError;
+use abi::seek::{SEEK_SET, SEEK_CUR, SEEK_END};
+use sys::{cvt, syscall};
+
+/// A file opened for reading, closed when dropped.
+#[derive(Debug)]
+pub struct File {
+    fd: usize,
+}
+
+impl File {
+    pub fn open(path: &Path) -> io::Result<File> {
+        let path = path.to_str()
+            .ok_or(io::Error::from_raw_os_error(OsError::InvalidArgument.code() as i32))?;
+        let fd = cvt(syscall::open(path))?;
+        Ok(File { fd })
+    }
+
+    pub fn fd(&self) -> usize {
+        self.fd
+    }
+
+    pub fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        cvt(syscall::read(self.fd, buf))
+    }
+
+    pub fn seek(&self, pos: SeekFrom) -> io::Result<u64> {
+        let (offset, whence) = match pos {
+            SeekFrom::Start(off) => (off, SEEK_SET),
+            SeekFrom::Current(off) => (off as u64, SEEK_CUR),
+            SeekFrom::End(off) => (off as u64, SEEK_END),
+        };
+        cvt(syscall::seek(self.fd, offset, whence))
+    }
+}
+
+impl Drop for File {
+    fn drop(&mut self) {
+        let _ = syscall::close(self.fd);
+    }
+}
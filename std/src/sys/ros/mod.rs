@@ -1,7 +1,34 @@
 use os::raw::c_char;
+use abi::OsError;
+
+pub mod syscall;
+pub mod mutex;
+pub mod condvar;
+pub mod time;
+pub mod thread;
+pub mod args;
+pub mod fs;
+pub mod stdio;
+
+pub fn decode_error_kind(errno: i32) -> ::io::ErrorKind {
+    use io::ErrorKind;
+
+    match OsError::from_code(errno as u64) {
+        OsError::NotFound => ErrorKind::NotFound,
+        OsError::PermissionDenied => ErrorKind::PermissionDenied,
+        OsError::AlreadyExists => ErrorKind::AlreadyExists,
+        OsError::InvalidArgument => ErrorKind::InvalidInput,
+        OsError::WouldBlock => ErrorKind::WouldBlock,
+        OsError::BrokenPipe => ErrorKind::BrokenPipe,
+        OsError::TimedOut => ErrorKind::TimedOut,
+        OsError::Interrupted => ErrorKind::Interrupted,
+        _ => ErrorKind::Other,
+    }
+}
 
-pub fn decode_error_kind(_errno: i32) -> ::io::ErrorKind {
-    ::io::ErrorKind::Other
+/// Converts the result of a system call into an `io::Result`.
+pub fn cvt<T>(r: Result<T, OsError>) -> ::io::Result<T> {
+    r.map_err(|e| ::io::Error::from_raw_os_error(e.code() as i32))
 }
 
 pub fn strlen(string: *const c_char) -> usize {
@@ -15,8 +42,8 @@ pub fn strlen(string: *const c_char) -> usize {
 
 pub mod os {
     /// Gets a detailed string description for the given error number.
-    pub fn error_string(_errno: i32) -> String {
-        "unknown error".to_string()
+    pub fn error_string(errno: i32) -> String {
+        ::abi::OsError::from_code(errno as u64).description().to_string()
     }
 
     /// Returns the platform-specific value of errno
@@ -0,0 +1,63 @@
+use cell::UnsafeCell;
+use intrinsics::{atomic_cxchg, atomic_xchg};
+
+use sys::syscall::{futex_wait, futex_wake};
+
+/// A lock that sleeps in the kernel while contended.
+///
+/// The lock word is `0` when unlocked, `1` when locked and `2` when locked
+/// with processes possibly sleeping on it. Uncontended locking and unlocking
+/// never enter the kernel.
+pub struct Mutex {
+    pub lock: UnsafeCell<u32>,
+}
+
+unsafe impl Send for Mutex {}
+unsafe impl Sync for Mutex {}
+
+pub unsafe fn mutex_try_lock(m: *mut u32) -> bool {
+    atomic_cxchg(m, 0, 1).0 == 0
+}
+
+pub unsafe fn mutex_lock(m: *mut u32) {
+    let mut c = atomic_cxchg(m, 0, 1).0;
+    if c == 0 {
+        return;
+    }
+
+    //mark the lock contended, then sleep until it is released
+    if c != 2 {
+        c = atomic_xchg(m, 2);
+    }
+    while c != 0 {
+        let _ = futex_wait(m, 2);
+        c = atomic_xchg(m, 2);
+    }
+}
+
+pub unsafe fn mutex_unlock(m: *mut u32) {
+    if atomic_xchg(m, 0) == 2 {
+        let _ = futex_wake(m, 1);
+    }
+}
+
+impl Mutex {
+    pub const fn new() -> Mutex {
+        Mutex { lock: UnsafeCell::new(0) }
+    }
+
+    #[inline]
+    pub unsafe fn try_lock(&self) -> bool {
+        mutex_try_lock(self.lock.get())
+    }
+
+    #[inline]
+    pub unsafe fn lock(&self) {
+        mutex_lock(self.lock.get())
+    }
+
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        mutex_unlock(self.lock.get())
+    }
+}
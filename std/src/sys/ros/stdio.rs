@@ -0,0 +1,49 @@
+use io;
+use sys::{cvt, syscall};
+
+/// File descriptors the kernel opens to the console for every user program.
+pub const STDIN_FD: usize = 0;
+pub const STDOUT_FD: usize = 1;
+pub const STDERR_FD: usize = 2;
+
+pub struct Stdin(());
+pub struct Stdout(());
+pub struct Stderr(());
+
+impl Stdin {
+    pub fn new() -> io::Result<Stdin> {
+        Ok(Stdin(()))
+    }
+
+    pub fn read(&self, data: &mut [u8]) -> io::Result<usize> {
+        cvt(syscall::read(STDIN_FD, data))
+    }
+}
+
+impl Stdout {
+    pub fn new() -> io::Result<Stdout> {
+        Ok(Stdout(()))
+    }
+
+    pub fn write(&self, data: &[u8]) -> io::Result<usize> {
+        cvt(syscall::write(STDOUT_FD, data))
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Stderr {
+    pub fn new() -> io::Result<Stderr> {
+        Ok(Stderr(()))
+    }
+
+    pub fn write(&self, data: &[u8]) -> io::Result<usize> {
+        cvt(syscall::write(STDERR_FD, data))
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
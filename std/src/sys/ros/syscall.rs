@@ -0,0 +1,92 @@
+//! Raw system calls into the kernel.
+//!
+//! Arguments are passed in `x0` to `x5`, the result comes back in `x0`, a
+//! second result in `x1` and the status in `x7`, where `0` means success. See
+//! the `abi` crate.
+
+use abi::{nr, OsError};
+
+/// Blocks the calling process while the word at `addr` holds `expected`.
+/// Fails with `OsError::WouldBlock` right away if it holds another value.
+pub unsafe fn futex_wait(addr: *const u32, expected: u32) -> Result<(), OsError> {
+    svc_raw!(nr::FUTEX_WAIT, addr, expected).2
+}
+
+/// Wakes up at most `n` processes blocked on the word at `addr`. Returns the
+/// number of processes woken.
+pub unsafe fn futex_wake(addr: *const u32, n: usize) -> Result<usize, OsError> {
+    let (n, _, r) = svc_raw!(nr::FUTEX_WAKE, addr, n);
+    r.map(|()| n as usize)
+}
+
+/// Terminates the calling process.
+pub fn exit() -> ! {
+    unsafe {
+        let _ = svc_raw!(nr::EXIT);
+    }
+    loop {}
+}
+
+/// Returns the ID of the calling process.
+pub fn getpid() -> u64 {
+    unsafe { svc_raw!(nr::GETPID).0 }
+}
+
+/// Gives up the CPU to the next ready process.
+pub fn sched_yield() {
+    unsafe {
+        let _ = svc_raw!(nr::YIELD);
+    }
+}
+
+/// Sleeps for `secs` seconds and `nanos` nanoseconds. If a signal interrupts
+/// the sleep, fails with `OsError::Interrupted` along with the seconds and
+/// nanoseconds that remained.
+pub fn nanosleep(secs: u64, nanos: u32) -> Result<(), (OsError, u64, u32)> {
+    let (secs, nanos, r) = unsafe { svc_raw!(nr::NANOSLEEP, secs, nanos) };
+    r.map_err(|e| (e, secs, nanos as u32))
+}
+
+/// Returns the seconds and nanoseconds of `clock`.
+pub fn clock_gettime(clock: u64) -> Result<(u64, u32), OsError> {
+    let (secs, nanos, r) = unsafe { svc_raw!(nr::CLOCK_GETTIME, clock) };
+    r.map(|()| (secs, nanos as u32))
+}
+
+/// Opens the file at the absolute `path` for reading and returns its file
+/// descriptor.
+pub fn open(path: &str) -> Result<usize, OsError> {
+    let (fd, _, r) = unsafe { svc_raw!(nr::OPEN, path.as_ptr(), path.len()) };
+    r.map(|()| fd as usize)
+}
+
+/// Reads from `fd` into `buf` and returns the number of bytes read.
+pub fn read(fd: usize, buf: &mut [u8]) -> Result<usize, OsError> {
+    let (n, _, r) = unsafe { svc_raw!(nr::READ, fd, buf.as_mut_ptr(), buf.len()) };
+    r.map(|()| n as usize)
+}
+
+/// Writes `buf` to `fd` and returns the number of bytes written.
+pub fn write(fd: usize, buf: &[u8]) -> Result<usize, OsError> {
+    let (n, _, r) = unsafe { svc_raw!(nr::WRITE, fd, buf.as_ptr(), buf.len()) };
+    r.map(|()| n as usize)
+}
+
+/// Moves the position of `fd` by `offset` from `whence` and returns the new
+/// position.
+pub fn seek(fd: usize, offset: u64, whence: u64) -> Result<u64, OsError> {
+    let (pos, _, r) = unsafe { svc_raw!(nr::SEEK, fd, offset, whence) };
+    r.map(|()| pos)
+}
+
+/// Closes `fd`.
+pub fn close(fd: usize) -> Result<(), OsError> {
+    unsafe { svc_raw!(nr::CLOSE, fd).2 }
+}
+
+/// Copies the NUL-terminated arguments of the calling process to `buf` if
+/// they fit, and returns the number of bytes they take.
+pub fn args(buf: &mut [u8]) -> Result<usize, OsError> {
+    let (n, _, r) = unsafe { svc_raw!(nr::ARGS, buf.as_mut_ptr(), buf.len()) };
+    r.map(|()| n as usize)
+}
@@ -0,0 +1,16 @@
+use time::Duration;
+use abi::clock::{self, Timespec};
+use sys::syscall;
+
+pub fn sleep(dur: Duration) {
+    //sleeps again for the remaining time when interrupted by a signal
+    let t = Timespec { secs: dur.as_secs(), nanos: dur.subsec_nanos() };
+    clock::sleep(t, |chunk| {
+        syscall::nanosleep(chunk.secs, chunk.nanos)
+            .map_err(|(e, secs, nanos)| (e, Timespec { secs, nanos }))
+    })
+}
+
+pub fn yield_now() {
+    syscall::sched_yield()
+}
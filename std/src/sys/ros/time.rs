@@ -0,0 +1,116 @@
+use fmt;
+use time::Duration;
+use abi::clock::{CLOCK_MONOTONIC, CLOCK_REALTIME, Timespec};
+use sys::syscall;
+
+/// The arithmetic of kernel clock times, kept in `abi` where it is tested.
+trait TimespecExt {
+    fn now(clock: u64) -> Timespec;
+    fn sub_timespec(&self, other: &Timespec) -> Result<Duration, Duration>;
+    fn add_duration(&self, other: &Duration) -> Timespec;
+    fn sub_duration(&self, other: &Duration) -> Timespec;
+}
+
+fn from_duration(d: &Duration) -> Timespec {
+    Timespec { secs: d.as_secs(), nanos: d.subsec_nanos() }
+}
+
+fn to_duration(t: Timespec) -> Duration {
+    Duration::new(t.secs, t.nanos)
+}
+
+impl TimespecExt for Timespec {
+    fn now(clock: u64) -> Timespec {
+        let (secs, nanos) = syscall::clock_gettime(clock).expect("clock_gettime failed");
+        Timespec { secs, nanos }
+    }
+
+    fn sub_timespec(&self, other: &Timespec) -> Result<Duration, Duration> {
+        match self.checked_sub(other) {
+            Some(d) => Ok(to_duration(d)),
+            None => Err(to_duration(other.checked_sub(self).unwrap())),
+        }
+    }
+
+    fn add_duration(&self, other: &Duration) -> Timespec {
+        self.checked_add(&from_duration(other))
+            .expect("overflow when adding duration to time")
+    }
+
+    fn sub_duration(&self, other: &Duration) -> Timespec {
+        self.checked_sub(&from_duration(other))
+            .expect("overflow when subtracting duration from time")
+    }
+}
+
+/// A time of the monotonic clock, counting from boot.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant {
+    t: Timespec,
+}
+
+/// A time of the wall clock, counting from the Unix epoch.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemTime {
+    t: Timespec,
+}
+
+pub const UNIX_EPOCH: SystemTime = SystemTime {
+    t: Timespec { secs: 0, nanos: 0 },
+};
+
+impl Instant {
+    pub fn now() -> Instant {
+        Instant { t: Timespec::now(CLOCK_MONOTONIC) }
+    }
+
+    pub fn sub_instant(&self, other: &Instant) -> Duration {
+        self.t.sub_timespec(&other.t).unwrap_or_else(|_| {
+            panic!("other was less than the current instant")
+        })
+    }
+
+    pub fn add_duration(&self, other: &Duration) -> Instant {
+        Instant { t: self.t.add_duration(other) }
+    }
+
+    pub fn sub_duration(&self, other: &Duration) -> Instant {
+        Instant { t: self.t.sub_duration(other) }
+    }
+}
+
+impl fmt::Debug for Instant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Instant")
+         .field("secs", &self.t.secs)
+         .field("nanos", &self.t.nanos)
+         .finish()
+    }
+}
+
+impl SystemTime {
+    pub fn now() -> SystemTime {
+        SystemTime { t: Timespec::now(CLOCK_REALTIME) }
+    }
+
+    pub fn sub_time(&self, other: &SystemTime) -> Result<Duration, Duration> {
+        self.t.sub_timespec(&other.t)
+    }
+
+    pub fn add_duration(&self, other: &Duration) -> SystemTime {
+        SystemTime { t: self.t.add_duration(other) }
+    }
+
+    pub fn sub_duration(&self, other: &Duration) -> SystemTime {
+        SystemTime { t: self.t.sub_duration(other) }
+    }
+}
+
+impl fmt::Debug for SystemTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SystemTime")
+         .field("secs", &self.t.secs)
+         .field("nanos", &self.t.nanos)
+         .finish()
+    }
+}
@@ -0,0 +1,30 @@
+#![feature(alloc)]
+#![no_std]
+
+extern crate alloc;
+extern crate user;
+
+use alloc::vec::Vec;
+use user::ipc::{self, MSG_SIZE};
+use user::process;
+
+/// Tells the parent process the ID of this one and the sum of the IDs up to it,
+/// computed on the heap.
+#[no_mangle]
+pub extern "C" fn main() {
+    let pid = match process::getpid() {
+        Ok(pid) => pid,
+        Err(_) => return,
+    };
+    let ids: Vec<u64> = (0..pid + 1).collect();
+    let sum: u64 = ids.iter().sum();
+
+    if let Ok(parent) = process::getppid() {
+        let mut msg = [0u8; MSG_SIZE];
+        for i in 0..8 {
+            msg[i] = (pid >> (8 * i)) as u8;
+            msg[8 + i] = (sum >> (8 * i)) as u8;
+        }
+        let _ = ipc::send(parent, &msg);
+    }
+}
@@ -0,0 +1,16 @@
+//! Futexes, the building block of blocking locks.
+
+use abi::nr;
+use OsError;
+
+/// Blocks while the word at `addr` holds `expected`. Fails with `WouldBlock`
+/// right away if it holds another value.
+pub unsafe fn futex_wait(addr: *const u32, expected: u32) -> Result<(), OsError> {
+    svc!(nr::FUTEX_WAIT, addr, expected).map(|_| ())
+}
+
+/// Wakes up at most `n` processes blocked on the word at `addr` and returns
+/// the number woken.
+pub unsafe fn futex_wake(addr: *const u32, n: usize) -> Result<usize, OsError> {
+    svc!(nr::FUTEX_WAKE, addr, n).map(|(n, _)| n as usize)
+}
@@ -0,0 +1,104 @@
+//! The heap behind `alloc`, grown with `mmap`.
+
+use core::cell::UnsafeCell;
+use alloc::heap::{Alloc, AllocErr, Layout};
+use mem::{self, PAGE_SIZE};
+
+/// Size of the chunks of memory requested from the kernel.
+const CHUNK: usize = 16 * PAGE_SIZE;
+
+/// Allocations of at least this many bytes get their own mapping, which is
+/// unmapped when they are freed.
+const LARGE: usize = CHUNK / 4;
+
+/// Align `addr` upwards to the nearest multiple of `align`, a power of two.
+fn align_up(addr: usize, align: usize) -> usize {
+    (addr + align - 1) & !(align - 1)
+}
+
+/// Smallest block handed out to small allocations, large enough to link it
+/// into a free list once it is freed.
+const MIN_BLOCK: usize = 16;
+
+/// Number of size classes of small blocks: `MIN_BLOCK` bytes doubling up to
+/// `LARGE` bytes.
+const CLASSES: usize = 11;
+
+/// Returns the size class of a small allocation of `layout`. Blocks of class
+/// `n` are `MIN_BLOCK << n` bytes, aligned to their size up to `PAGE_SIZE`, so
+/// a freed block fits any later allocation of its class.
+fn class(layout: &Layout) -> usize {
+    let size = layout.size().max(layout.align()).max(MIN_BLOCK).next_power_of_two();
+    (size / MIN_BLOCK).trailing_zeros() as usize
+}
+
+/// The small allocations: freed blocks are kept in a free list per size
+/// class and reused first, other blocks are carved from the current chunk,
+/// from `current` up to `end`.
+struct Small {
+    current: usize,
+    end: usize,
+    /// The first freed block of each class, `0` if there is none. Each freed
+    /// block holds the address of the next one in its first word.
+    free: [usize; CLASSES],
+}
+
+/// An allocator over chunks of `mmap` memory.
+///
+/// A process has a single thread of execution, and signal handlers must not
+/// allocate, so the heap is not locked.
+pub struct Heap(UnsafeCell<Small>);
+
+unsafe impl Sync for Heap {}
+
+impl Heap {
+    /// Returns an empty heap. The first allocation maps its first chunk.
+    pub const fn new() -> Heap {
+        Heap(UnsafeCell::new(Small { current: 0, end: 0, free: [0; CLASSES] }))
+    }
+}
+
+unsafe impl<'a> Alloc for &'a Heap {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<*mut u8, AllocErr> {
+        if layout.size() >= LARGE || layout.align() > PAGE_SIZE {
+            if layout.align() > PAGE_SIZE {
+                return Err(AllocErr::Unsupported { details: "alignment above page size" });
+            }
+            return mem::mmap(layout.size())
+                .map(|(addr, _)| addr)
+                .map_err(|_| AllocErr::Exhausted { request: layout });
+        }
+
+        let small = &mut *self.0.get();
+        let class = class(&layout);
+        let block = small.free[class];
+        if block != 0 {
+            small.free[class] = *(block as *const usize);
+            return Ok(block as *mut u8);
+        }
+
+        let size = MIN_BLOCK << class;
+        let align = size.min(PAGE_SIZE);
+        let mut start = align_up(small.current, align);
+        if small.current == 0 || start + size > small.end {
+            let (addr, chunk) = mem::mmap(CHUNK)
+                .map_err(|_| AllocErr::Exhausted { request: layout.clone() })?;
+            small.end = addr as usize + chunk;
+            start = align_up(addr as usize, align);
+        }
+        small.current = start + size;
+        Ok(start as *mut u8)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: *mut u8, layout: Layout) {
+        if layout.size() >= LARGE {
+            let _ = mem::munmap(ptr);
+            return;
+        }
+
+        let small = &mut *self.0.get();
+        let class = class(&layout);
+        *(ptr as *mut usize) = small.free[class];
+        small.free[class] = ptr as usize;
+    }
+}
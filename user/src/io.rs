@@ -0,0 +1,41 @@
+//! Pipes, files and file descriptors.
+
+use abi::nr;
+
+pub use abi::seek::{SEEK_SET, SEEK_CUR, SEEK_END};
+use OsError;
+
+/// Type alias for the type of a file descriptor.
+pub type Fd = usize;
+
+/// Creates a pipe and returns its read end and write end.
+pub fn pipe() -> Result<(Fd, Fd), OsError> {
+    unsafe { svc!(nr::PIPE).map(|(r, w)| (r as Fd, w as Fd)) }
+}
+
+/// Opens the file at the absolute `path` for reading.
+pub fn open(path: &str) -> Result<Fd, OsError> {
+    unsafe { svc!(nr::OPEN, path.as_ptr(), path.len()).map(|(fd, _)| fd as Fd) }
+}
+
+/// Reads from `fd` into `buf` and returns the number of bytes read, `0` at
+/// end-of-file.
+pub fn read(fd: Fd, buf: &mut [u8]) -> Result<usize, OsError> {
+    unsafe { svc!(nr::READ, fd, buf.as_mut_ptr(), buf.len()).map(|(n, _)| n as usize) }
+}
+
+/// Writes `buf` to `fd` and returns the number of bytes written.
+pub fn write(fd: Fd, buf: &[u8]) -> Result<usize, OsError> {
+    unsafe { svc!(nr::WRITE, fd, buf.as_ptr(), buf.len()).map(|(n, _)| n as usize) }
+}
+
+/// Moves the position of the file `fd` to `offset` from `whence`, one of the
+/// `SEEK_*` constants, and returns the new position.
+pub fn seek(fd: Fd, offset: i64, whence: u64) -> Result<u64, OsError> {
+    unsafe { svc!(nr::SEEK, fd, offset, whence).map(|(pos, _)| pos) }
+}
+
+/// Closes `fd`.
+pub fn close(fd: Fd) -> Result<(), OsError> {
+    unsafe { svc!(nr::CLOSE, fd).map(|_| ()) }
+}
@@ -0,0 +1,45 @@
+//! Messages and shared memory.
+
+use abi::nr;
+use process::Pid;
+use OsError;
+
+pub use abi::{MSG_SIZE, NO_TIMEOUT};
+
+/// The payload of a message.
+pub type Message = [u8; MSG_SIZE];
+
+/// Sends `msg` to process `pid` without blocking. Fails with `WouldBlock` if
+/// its mailbox is full.
+pub fn send(pid: Pid, msg: &Message) -> Result<(), OsError> {
+    unsafe { svc!(nr::SEND, pid, msg.as_ptr()).map(|_| ()) }
+}
+
+/// Receives the oldest message into `msg` and returns the ID of its sender,
+/// waiting up to `timeout_ms` milliseconds, or forever with `NO_TIMEOUT`.
+pub fn recv(msg: &mut Message, timeout_ms: u64) -> Result<Pid, OsError> {
+    unsafe { svc!(nr::RECV, msg.as_mut_ptr(), timeout_ms).map(|(pid, _)| pid) }
+}
+
+/// Creates the shared memory region `name` of at least `size` bytes and maps
+/// it. The region stays mapped until `shm_drop`.
+pub fn shm_create(name: &str, size: usize) -> Result<&'static mut [u8], OsError> {
+    unsafe {
+        svc!(nr::SHM_CREATE, name.as_ptr(), name.len(), size)
+            .map(|(addr, size)| ::core::slice::from_raw_parts_mut(addr as *mut u8, size as usize))
+    }
+}
+
+/// Maps the existing shared memory region `name`.
+pub fn shm_map(name: &str) -> Result<&'static mut [u8], OsError> {
+    unsafe {
+        svc!(nr::SHM_MAP, name.as_ptr(), name.len())
+            .map(|(addr, size)| ::core::slice::from_raw_parts_mut(addr as *mut u8, size as usize))
+    }
+}
+
+/// Drops the mapping of the shared memory region at `addr`. The memory must
+/// not be used afterwards.
+pub unsafe fn shm_drop(addr: *mut u8) -> Result<(), OsError> {
+    svc!(nr::SHM_DROP, addr).map(|_| ())
+}
@@ -0,0 +1,48 @@
+//! Runtime and system call library for user programs.
+//!
+//! A program is a `no_std` static library that depends on this crate and
+//! defines its entry point as:
+//!
+//! ```rust
+//! #[no_mangle]
+//! pub extern "C" fn main() { }
+//! ```
+//!
+//! It is linked with `layout.ld` into a standalone, position-independent
+//! binary (see the `Makefile`) starting with the header of `abi::image`, and
+//! started from the kernel shell with `run <path> [args...]`. `_start`
+//! relocates the binary to the address it was loaded at, zeroes `.bss`, calls
+//! `main` and exits. The heap used by
+//! `alloc` grows with the `mmap` system call.
+//!
+//! Every system call has a typed wrapper in one of the modules below, returning
+//! `OsError` on failure.
+
+#![feature(asm)]
+#![feature(lang_items)]
+#![feature(naked_functions)]
+#![feature(const_fn)]
+#![feature(alloc, allocator_api, global_allocator)]
+
+#![no_std]
+
+#[macro_use]
+extern crate abi;
+extern crate alloc;
+
+mod heap;
+mod rt;
+
+pub mod process;
+pub mod io;
+pub mod ipc;
+pub mod signal;
+pub mod futex;
+pub mod mem;
+pub mod time;
+
+pub use abi::OsError;
+
+/// The allocator behind `alloc`.
+#[global_allocator]
+static HEAP: heap::Heap = heap::Heap::new();
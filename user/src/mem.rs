@@ -0,0 +1,18 @@
+//! Private memory.
+
+use abi::nr;
+use OsError;
+
+pub use abi::PAGE_SIZE;
+
+/// Maps at least `size` bytes of zeroed, page-aligned memory and returns its
+/// address and size.
+pub fn mmap(size: usize) -> Result<(*mut u8, usize), OsError> {
+    unsafe { svc!(nr::MMAP, size).map(|(addr, size)| (addr as *mut u8, size as usize)) }
+}
+
+/// Unmaps the memory at `addr` returned by `mmap`. The memory must not be
+/// used afterwards.
+pub unsafe fn munmap(addr: *mut u8) -> Result<(), OsError> {
+    svc!(nr::MUNMAP, addr).map(|_| ())
+}
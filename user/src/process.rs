@@ -0,0 +1,76 @@
+//! Processes, resource limits and CPU affinity.
+
+use abi::nr;
+use OsError;
+
+/// Type alias for the type of a process ID.
+pub type Pid = u64;
+
+pub use abi::resource::{MEMORY, FILES, CHILDREN, CPU_TIME, UNLIMITED};
+
+/// Exits the calling process.
+pub fn exit() -> ! {
+    unsafe {
+        let _ = svc!(nr::EXIT);
+    }
+    loop {}
+}
+
+/// Sleeps for `ms` milliseconds and returns the milliseconds that actually
+/// elapsed. Fails with `Interrupted` if a signal arrives first.
+pub fn sleep(ms: u32) -> Result<u32, OsError> {
+    unsafe { svc!(nr::SLEEP, ms).map(|(elapsed, _)| elapsed as u32) }
+}
+
+/// Copies the name of the calling process followed by its arguments to `buf`,
+/// each terminated by a NUL byte, and returns the number of bytes they take.
+/// Nothing is copied if they do not fit.
+pub fn args(buf: &mut [u8]) -> Result<usize, OsError> {
+    unsafe { svc!(nr::ARGS, buf.as_mut_ptr(), buf.len()).map(|(n, _)| n as usize) }
+}
+
+/// Returns the ID of the calling process.
+pub fn getpid() -> Result<Pid, OsError> {
+    unsafe { svc!(nr::GETPID).map(|(pid, _)| pid) }
+}
+
+/// Returns the ID of the parent of the calling process. Fails with
+/// `NoSuchProcess` if it has none.
+pub fn getppid() -> Result<Pid, OsError> {
+    unsafe { svc!(nr::GETPPID).map(|(pid, _)| pid) }
+}
+
+/// Returns the limit of `resource`, one of the constants of this module, and
+/// the amount of it the calling process uses.
+pub fn getrlimit(resource: u64) -> Result<(u64, u64), OsError> {
+    unsafe { svc!(nr::GETRLIMIT, resource) }
+}
+
+/// Lowers the limit of `resource` to `limit` and returns the previous limit.
+pub fn setrlimit(resource: u64, limit: u64) -> Result<u64, OsError> {
+    unsafe { svc!(nr::SETRLIMIT, resource, limit).map(|(old, _)| old) }
+}
+
+/// Restricts process `pid`, the caller or one of its descendants, to the
+/// cores set in `mask` and returns its previous mask.
+pub fn sched_setaffinity(pid: Pid, mask: usize) -> Result<usize, OsError> {
+    unsafe { svc!(nr::SCHED_SETAFFINITY, pid, mask).map(|(old, _)| old as usize) }
+}
+
+/// Returns the mask of cores process `pid` may run on.
+pub fn sched_getaffinity(pid: Pid) -> Result<usize, OsError> {
+    unsafe { svc!(nr::SCHED_GETAFFINITY, pid).map(|(mask, _)| mask as usize) }
+}
+
+/// Gives up the CPU to the next ready process.
+pub fn sched_yield() {
+    unsafe {
+        let _ = svc!(nr::YIELD);
+    }
+}
+
+/// Returns the length of a time slice in microseconds and the priority of
+/// process `pid`.
+pub fn sched_getparam(pid: Pid) -> Result<(u64, u64), OsError> {
+    unsafe { svc!(nr::SCHED_GETPARAM, pid) }
+}
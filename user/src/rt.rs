@@ -0,0 +1,71 @@
+//! Program entry and exit.
+
+use process;
+
+extern "C" {
+    fn main();
+}
+
+/// A relocation entry of `.rela.dyn`.
+#[repr(C)]
+struct Rela {
+    offset: usize,
+    info: usize,
+    addend: usize,
+}
+
+/// Type of a relocation that adds the load address to its addend.
+const R_AARCH64_RELATIVE: usize = 1027;
+
+/// Returns the run-time address of symbol `$sym`, computed relative to the
+/// program counter.
+macro_rules! symbol_addr {
+    ($sym:expr) => {{
+        let addr: usize;
+        asm!(concat!("adrp $0, ", $sym, "
+                      add $0, $0, :lo12:", $sym)
+             : "=r"(addr) ::: "volatile");
+        addr
+    }};
+}
+
+/// The entry point of the program, placed right after the header at the start
+/// of `.text`, which branches to it. The binary is linked at address `0`, so
+/// the address of `__image_start` is the load offset every absolute address is
+/// relocated by.
+#[no_mangle]
+#[link_section = ".text._start"]
+pub unsafe extern "C" fn _start() -> ! {
+    let base = symbol_addr!("__image_start");
+    let rela_start = symbol_addr!("__rela_start") as *const Rela;
+    let rela_end = symbol_addr!("__rela_end") as *const Rela;
+    let bss_start = symbol_addr!("__bss_start") as *mut u8;
+    let bss_end = symbol_addr!("__bss_end") as *mut u8;
+
+    let mut rela = rela_start;
+    while rela < rela_end {
+        let r = &*rela;
+        if r.info & 0xffffffff == R_AARCH64_RELATIVE {
+            let target = base.wrapping_add(r.offset) as *mut usize;
+            *target = base.wrapping_add(r.addend);
+        }
+        rela = rela.offset(1);
+    }
+
+    let mut p = bss_start;
+    while p < bss_end {
+        *p = 0;
+        p = p.offset(1);
+    }
+
+    main();
+    process::exit()
+}
+
+#[lang = "panic_fmt"]
+#[no_mangle]
+pub extern fn panic_fmt(_fmt: ::core::fmt::Arguments, _file: &'static str, _line: u32, _col: u32) -> ! {
+    process::exit()
+}
+
+#[lang = "eh_personality"] pub extern fn eh_personality() {}
@@ -0,0 +1,64 @@
+//! Signals.
+
+use abi::nr;
+use abi::signal::{SIG_DFL, SIG_IGN};
+use process::Pid;
+use OsError;
+
+pub use abi::signal::{Signal, NSIG, SIGINT, SIGKILL, SIGUSR1, SIGUSR2, SIGTERM, SIGCHLD};
+pub use abi::signal::{SIG_BLOCK, SIG_UNBLOCK, SIG_SETMASK};
+
+/// The action taken when a signal is delivered.
+#[derive(Copy, Clone)]
+pub enum Handler {
+    /// The default action of the signal.
+    Default,
+    /// The signal is discarded.
+    Ignore,
+    /// The function is called with the signal number.
+    Function(extern "C" fn(Signal)),
+}
+
+impl Handler {
+    fn to_u64(self) -> u64 {
+        match self {
+            Handler::Default => SIG_DFL,
+            Handler::Ignore => SIG_IGN,
+            Handler::Function(f) => f as u64,
+        }
+    }
+
+    fn from_u64(h: u64) -> Handler {
+        match h {
+            SIG_DFL => Handler::Default,
+            SIG_IGN => Handler::Ignore,
+            f => Handler::Function(unsafe { ::core::mem::transmute(f as usize) }),
+        }
+    }
+}
+
+/// Sends `sig` to process `pid`.
+pub fn kill(pid: Pid, sig: Signal) -> Result<(), OsError> {
+    unsafe { svc!(nr::KILL, pid, sig).map(|_| ()) }
+}
+
+/// Sets the action of `sig` to `handler` and returns the previous one.
+pub fn sigaction(sig: Signal, handler: Handler) -> Result<Handler, OsError> {
+    unsafe {
+        svc!(nr::SIGACTION, sig, handler.to_u64(), restorer as u64)
+            .map(|(old, _)| Handler::from_u64(old))
+    }
+}
+
+/// Changes the blocked signal mask as selected by `how`, one of `SIG_BLOCK`,
+/// `SIG_UNBLOCK` and `SIG_SETMASK`, and returns the previous mask.
+pub fn sigprocmask(how: u64, set: u32) -> Result<u32, OsError> {
+    unsafe { svc!(nr::SIGPROCMASK, how, set).map(|(old, _)| old as u32) }
+}
+
+/// Where signal handlers return to: resumes the interrupted code.
+#[naked]
+unsafe extern "C" fn restorer() -> ! {
+    asm!("svc $0" :: "i"(nr::SIGRETURN) :: "volatile");
+    loop {}
+}
@@ -0,0 +1,26 @@
+//! Clocks and sleeping.
+
+use abi::nr;
+use OsError;
+
+pub use abi::clock::{CLOCK_MONOTONIC, CLOCK_REALTIME, NANOS_PER_SEC, Timespec};
+
+/// Returns the time of `clock`: since boot for `CLOCK_MONOTONIC`, since the
+/// Unix epoch for `CLOCK_REALTIME`.
+pub fn clock_gettime(clock: u64) -> Result<Timespec, OsError> {
+    unsafe {
+        svc!(nr::CLOCK_GETTIME, clock).map(|(secs, nanos)| Timespec { secs, nanos: nanos as u32 })
+    }
+}
+
+/// Sleeps for `t`. If a signal interrupts the sleep, fails with
+/// `Interrupted` along with the time that remained.
+pub fn nanosleep(t: Timespec) -> Result<(), (OsError, Timespec)> {
+    let (secs, nanos, result) = unsafe { svc_raw!(nr::NANOSLEEP, t.secs, t.nanos) };
+    result.map_err(|e| (e, Timespec { secs, nanos: nanos as u32 }))
+}
+
+/// Sets the wall clock to `t` after the Unix epoch.
+pub fn settime(t: Timespec) -> Result<(), OsError> {
+    unsafe { svc!(nr::SETTIME, t.secs, t.nanos).map(|_| ()) }
+}